@@ -1,5 +1,5 @@
 use bevy::prelude::*;
-use pvw_rrect_physics::*;
+use pvw_rrect_physics::prelude::*;
 
 const TILE_SIZE: f32 = 40.0;
 const TILE_SIZE_VEC: Vec2 = Vec2::splat(TILE_SIZE);
@@ -100,6 +100,8 @@ fn player_movement(
         id: "player_movement".to_string(),
         active: Some(true),
         force: Some(force),
+        group: Some(ForceGroup::Input),
+        ..default()
     });
 }
 
@@ -109,6 +111,6 @@ fn message(mut msgs: MessageReader<CollisionMessage>, query: Query<&Name>) {
             continue;
         };
 
-        println!("{} collided with {}", n1, n2);
+        println!("{} collided with {} on its {:?} side", n1, n2, m.2.side);
     }
 }
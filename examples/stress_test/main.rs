@@ -3,7 +3,7 @@ use bevy::{
     prelude::*,
     window::PrimaryWindow,
 };
-use pvw_rrect_physics::*;
+use pvw_rrect_physics::prelude::*;
 use rand::Rng;
 
 const TILE_SIZE: f32 = 40.0;
@@ -16,16 +16,18 @@ struct CursorPos {
 }
 
 #[derive(Message)]
-struct SpawnBob(Vec2);
+struct SpawnBob(PhysicsPos);
 
 fn main() -> AppExit {
     let mut app = App::new();
     app.add_plugins(DefaultPlugins);
     app.add_plugins(FrameTimeDiagnosticsPlugin::default());
     app.add_plugins(LogDiagnosticsPlugin::default());
-    app.add_plugins(PvwRRectPhysicsPlugin {
-        spatial_grid_size: 4.0, // Smaller grid size for more optimization
-    });
+    app.add_plugins(
+        PhysicsPluginBuilder::new()
+            .cell_size(4.0) // Smaller grid size for more optimization
+            .build(),
+    );
     app.insert_resource(TileSize::new(TILE_SIZE));
     app.init_resource::<CursorPos>();
     app.add_message::<SpawnBob>();
@@ -73,14 +75,18 @@ fn should_bob_spawn(
     mut events: MessageWriter<SpawnBob>,
     click: Res<ButtonInput<MouseButton>>,
     cursor: Res<CursorPos>,
+    tile_size: Res<TileSize>,
 ) {
+    // the cursor is in pixels, bodies are positioned in tiles
+    let pos = tile_size.to_physics(WorldPos(cursor.position));
+
     if click.just_pressed(MouseButton::Left) {
-        events.write(SpawnBob(cursor.position / TILE_SIZE));
+        events.write(SpawnBob(pos));
     }
 
     if click.just_pressed(MouseButton::Right) {
         for _ in 0..10 {
-            events.write(SpawnBob(cursor.position / TILE_SIZE));
+            events.write(SpawnBob(pos));
         }
     }
 }
@@ -97,11 +103,12 @@ fn spawn_bob(mut commands: Commands, mut events: MessageReader<SpawnBob>) {
                 rng.random_range(-7.0..7.0),
             )),
             active: Some(false),
+            ..default()
         });
 
         commands.spawn((
             Name::new("Bob"),
-            Position(*pos),
+            Position::from(*pos),
             movement,
             Collider {
                 ctype: ColliderType::Dynamic(rng.random_range(1.0..20.0)), // Random mass
@@ -0,0 +1,71 @@
+use crate::*;
+use bevy_ecs::prelude::*;
+use bevy_platform::collections::HashSet;
+
+/// Which `PhysicsGroup`s are disabled. Every group is enabled by default.
+///
+/// Bodies in a disabled group keep their components but are neither moved nor collided with,
+/// and spatial queries do not find them. Toggling a group only changes this resource, so it costs
+/// the same for a thousand entities as for one.
+#[derive(Resource, Default, Clone, Debug)]
+pub struct PhysicsGroups {
+    disabled: HashSet<u32>,
+}
+
+impl PhysicsGroups {
+    pub fn enable(&mut self, group: u32) {
+        self.disabled.remove(&group);
+    }
+
+    pub fn disable(&mut self, group: u32) {
+        self.disabled.insert(group);
+    }
+
+    pub fn is_enabled(&self, group: u32) -> bool {
+        !self.disabled.contains(&group)
+    }
+
+    /// Returns true if the entity with the optional `PhysicsGroup` is simulated.
+    pub(crate) fn is_active(&self, group: Option<&PhysicsGroup>) -> bool {
+        group.is_none_or(|group| self.is_enabled(group.0))
+    }
+}
+
+/// Adds `enable_group()` and `disable_group()` to `Commands`.
+pub trait PhysicsGroupCommandsExt {
+    /// Resumes the simulation of every entity with `PhysicsGroup(group)`.
+    fn enable_group(&mut self, group: u32);
+    /// Stops the simulation of every entity with `PhysicsGroup(group)`.
+    fn disable_group(&mut self, group: u32);
+}
+
+impl PhysicsGroupCommandsExt for Commands<'_, '_> {
+    fn enable_group(&mut self, group: u32) {
+        self.queue(move |world: &mut World| {
+            world.resource_mut::<PhysicsGroups>().enable(group);
+        });
+    }
+
+    fn disable_group(&mut self, group: u32) {
+        self.queue(move |world: &mut World| {
+            world.resource_mut::<PhysicsGroups>().disable(group);
+        });
+    }
+}
+
+/// Stops integrating bodies in a disabled `PhysicsGroup`.
+pub(crate) fn skip_disabled_groups(
+    mut lod_steps: ResMut<lod::LodSteps>,
+    groups: Res<PhysicsGroups>,
+    bodies: Query<(Entity, &PhysicsGroup), With<Movement>>,
+) {
+    if groups.disabled.is_empty() {
+        return;
+    }
+
+    for (entity, group) in &bodies {
+        if !groups.is_enabled(group.0) {
+            lod_steps.skip(entity);
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use crate::*;
+use bevy_ecs::prelude::*;
+use bevy_math::prelude::*;
+
+/// Keeps bodies inside `PhysicsConfig::arena`.
+pub(crate) fn keep_in_arena(
+    mut query: Query<(
+        &mut Position,
+        &mut Movement,
+        Option<&Collider>,
+        Option<&CollisionLayers>,
+        Option<&PhysicsGroup>,
+    )>,
+    config: Res<PhysicsConfig>,
+    groups: Res<PhysicsGroups>,
+) {
+    let Some(arena) = config.arena else {
+        return;
+    };
+
+    for (mut pos, mut movement, collider, layers, group) in &mut query {
+        if !groups.is_active(group)
+            || collider.is_some_and(|collider| matches!(collider.ctype, ColliderType::Static))
+            || !arena
+                .layers
+                .interacts_with(&layers.copied().unwrap_or_default())
+        {
+            continue;
+        }
+
+        let half_size = collider.map_or(Vec2::ZERO, |collider| collider.size * 0.5);
+        let mut new_pos = pos.0;
+
+        for (axis, response) in [(Vec2::X, arena.x), (Vec2::Y, arena.y)] {
+            let (min, max) = (arena.min.dot(axis), arena.max.dot(axis));
+            let along = new_pos.dot(axis);
+
+            let restitution = match response {
+                BoundaryResponse::Wrap => {
+                    if max > min {
+                        new_pos += axis * (min + (along - min).rem_euclid(max - min) - along);
+                    }
+                    continue;
+                },
+                BoundaryResponse::Slide => 0.0,
+                BoundaryResponse::Bounce(restitution) => restitution,
+            };
+
+            let half_size = half_size.dot(axis);
+            let (edge, normal) = if along < min + half_size {
+                (min + half_size, axis)
+            } else if along > max - half_size {
+                (max - half_size, -axis)
+            } else {
+                continue;
+            };
+
+            new_pos += axis * (edge - along);
+
+            // the edge acts like a wall, so the forces bounce off it like off any surface
+            movement.reflect_velocity(normal, restitution);
+            let into_edge = movement.velocity.dot(normal);
+            if into_edge < 0.0 {
+                movement.velocity -= normal * into_edge * (1.0 + restitution.max(0.0));
+            }
+        }
+
+        if new_pos != pos.0 {
+            pos.0 = new_pos;
+        }
+    }
+}
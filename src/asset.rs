@@ -0,0 +1,246 @@
+use crate::*;
+use bevy_app::prelude::*;
+use bevy_asset::{AssetLoader, LoadContext, io::Reader, prelude::*};
+use bevy_ecs::prelude::*;
+use bevy_math::prelude::*;
+use bevy_reflect::TypePath;
+use serde::Deserialize;
+
+/// Physics components of an entity, loaded from a `.physics.ron` file.
+///
+/// ```ron
+/// (
+///     collider: (size: (1.0, 1.0), radius: 0.25, ctype: Dynamic(1.0)),
+///     damping: (8.0, 8.0),
+///     layers: Some((memberships: [1], filters: [0, 2])),
+///     material: (friction: Some((2.0, 2.0)), density: Some(1.5)),
+/// )
+/// ```
+///
+/// Only `collider` is required. Spawn an entity with `PhysicsPrefab` to apply it.
+#[derive(Asset, TypePath, Clone, Debug, Deserialize)]
+pub struct PhysicsPrefabAsset {
+    pub collider: Collider,
+    /// `Movement::damping` of the entity
+    #[serde(default)]
+    pub damping: Vec2,
+    #[serde(default)]
+    pub damping_mode: DampingMode,
+    #[serde(default)]
+    pub layers: Option<CollisionLayers>,
+    #[serde(default)]
+    pub push_priority: Option<PushPriority>,
+    #[serde(default)]
+    pub material: PhysicsMaterial,
+}
+
+/// Surface and mass properties of a prefab.
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+pub struct PhysicsMaterial {
+    /// Inserted as `Friction`
+    #[serde(default)]
+    pub friction: Option<Vec2>,
+    /// Inserted as `Density`
+    #[serde(default)]
+    pub density: Option<f32>,
+}
+
+/// Inserts the components of a `PhysicsPrefabAsset` once it is loaded, and again whenever the
+/// file changes.
+///
+/// The forces of an existing `Movement` are kept, only its damping is replaced.
+#[derive(Component, Clone, Debug)]
+pub struct PhysicsPrefab(pub Handle<PhysicsPrefabAsset>);
+
+/// Loads `PhysicsPrefabAsset`s and applies them to entities with `PhysicsPrefab`, and
+/// `PhysicsConfigAsset`s to the `PhysicsConfig` resource with `PhysicsConfigHandle`.
+///
+/// Requires the `AssetPlugin`.
+pub struct PhysicsPrefabPlugin;
+
+impl Plugin for PhysicsPrefabPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<PhysicsPrefabAsset>();
+        app.init_asset::<PhysicsConfigAsset>();
+        app.register_asset_loader(PhysicsPrefabLoader);
+        app.register_asset_loader(PhysicsConfigLoader);
+        app.add_systems(Update, (apply_physics_prefabs, apply_physics_config));
+    }
+}
+
+/// `PhysicsConfig` loaded from a `.physics_config.ron` file, e.g. to tune the game feel without
+/// recompiling.
+///
+/// The file contains a `PhysicsConfig` in RON, fields that are left out keep their default:
+///
+/// ```ron
+/// (gravity: (0.0, -20.0), slop: 0.01, solver_iterations: 8)
+/// ```
+#[derive(Asset, TypePath, Clone, Debug, Deserialize)]
+#[serde(transparent)]
+pub struct PhysicsConfigAsset(pub PhysicsConfig);
+
+/// Replaces the `PhysicsConfig` resource with the config in this asset once it is loaded, and
+/// again whenever the file changes if the `AssetPlugin` watches for changes.
+#[derive(Resource, Clone, Debug)]
+pub struct PhysicsConfigHandle(pub Handle<PhysicsConfigAsset>);
+
+#[derive(Default)]
+struct PhysicsPrefabLoader;
+
+impl AssetLoader for PhysicsPrefabLoader {
+    type Asset = PhysicsPrefabAsset;
+    type Settings = ();
+    type Error = PhysicsAssetError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<PhysicsPrefabAsset, PhysicsAssetError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let prefab: PhysicsPrefabAsset = ron::de::from_bytes(&bytes)?;
+        prefab.collider.validate()?;
+        Ok(prefab)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["physics.ron"]
+    }
+}
+
+#[derive(Default)]
+struct PhysicsConfigLoader;
+
+impl AssetLoader for PhysicsConfigLoader {
+    type Asset = PhysicsConfigAsset;
+    type Settings = ();
+    type Error = PhysicsAssetError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<PhysicsConfigAsset, PhysicsAssetError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["physics_config.ron"]
+    }
+}
+
+/// Reason a physics asset file could not be loaded.
+#[derive(Debug)]
+pub enum PhysicsAssetError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+    /// The file was read, but describes an invalid collider
+    Collider(ColliderError),
+}
+
+impl std::fmt::Display for PhysicsAssetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "could not read physics asset: {err}"),
+            Self::Ron(err) => write!(f, "could not parse physics asset: {err}"),
+            Self::Collider(err) => write!(f, "invalid collider in physics asset: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for PhysicsAssetError {}
+
+impl From<std::io::Error> for PhysicsAssetError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for PhysicsAssetError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        Self::Ron(err)
+    }
+}
+
+impl From<ColliderError> for PhysicsAssetError {
+    fn from(err: ColliderError) -> Self {
+        Self::Collider(err)
+    }
+}
+
+fn apply_physics_prefabs(
+    mut commands: Commands,
+    mut events: MessageReader<AssetEvent<PhysicsPrefabAsset>>,
+    prefabs: Res<Assets<PhysicsPrefabAsset>>,
+    query: Query<(Entity, Ref<PhysicsPrefab>, Option<&Movement>)>,
+) {
+    let changed: Vec<AssetId<PhysicsPrefabAsset>> = events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+
+    for (entity, prefab_handle, movement) in &query {
+        // entities spawned before their prefab finished loading are handled by its load event
+        if !prefab_handle.is_changed() && !changed.contains(&prefab_handle.0.id()) {
+            continue;
+        }
+
+        let Some(prefab) = prefabs.get(&prefab_handle.0) else {
+            continue;
+        };
+
+        let mut movement = movement.cloned().unwrap_or_default();
+        movement.damping = prefab.damping;
+        movement.damping_mode = prefab.damping_mode;
+
+        let mut entity = commands.entity(entity);
+        entity.insert((prefab.collider, movement));
+        if let Some(layers) = prefab.layers {
+            entity.insert(layers);
+        }
+        if let Some(push_priority) = prefab.push_priority {
+            entity.insert(push_priority);
+        }
+        if let Some(friction) = prefab.material.friction {
+            entity.insert(Friction(friction));
+        }
+        if let Some(density) = prefab.material.density {
+            entity.insert(Density(density));
+        }
+    }
+}
+
+fn apply_physics_config(
+    mut commands: Commands,
+    mut events: MessageReader<AssetEvent<PhysicsConfigAsset>>,
+    configs: Res<Assets<PhysicsConfigAsset>>,
+    handle: Option<Res<PhysicsConfigHandle>>,
+) {
+    let Some(handle) = handle else {
+        events.clear();
+        return;
+    };
+
+    let reloaded = events.read().any(|event| match event {
+        AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => {
+            *id == handle.0.id()
+        },
+        _ => false,
+    });
+
+    if (reloaded || handle.is_changed())
+        && let Some(config) = configs.get(&handle.0)
+    {
+        commands.insert_resource(config.0.clone());
+    }
+}
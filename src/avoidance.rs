@@ -0,0 +1,64 @@
+use crate::*;
+use bevy_ecs::prelude::*;
+use bevy_math::prelude::*;
+
+/// Applies the separation force of every `Avoidance`.
+///
+/// Uses the grid of the previous tick, which is close enough for steering.
+pub(crate) fn apply_avoidance(
+    query: Query<(Entity, &Avoidance, &Position)>,
+    mut movements: Query<&mut Movement>,
+    bodies: Query<(&Position, &Collider), Without<SimplifiedPhysics>>,
+    grid: Res<SpatialHashGrid>,
+    config: Res<PhysicsConfig>,
+    mut removed: RemovedComponents<Avoidance>,
+) {
+    for (entity, avoidance, pos) in &query {
+        let radius = avoidance.radius.max(0.0);
+        let mut force = Vec2::ZERO;
+
+        for other in grid.entities_in_rect(pos.0 - radius, pos.0 + radius) {
+            if other == entity {
+                continue;
+            }
+
+            let Ok((other_pos, collider)) = bodies.get(other) else {
+                continue;
+            };
+
+            if !matches!(collider.ctype, ColliderType::Dynamic(_)) {
+                continue;
+            }
+
+            // the closest image of the other body on a torus
+            let other_pos = pos.0 + config.topology.delta(pos.0, other_pos.0);
+            let distance = collider.sdf(other_pos, pos.0);
+            if distance >= radius {
+                continue;
+            }
+
+            let weight = if radius > 0.0 {
+                (1.0 - distance / radius).min(1.0)
+            } else {
+                1.0
+            };
+            force += collider.gradient(other_pos, pos.0) * weight * avoidance.strength;
+        }
+
+        if let Ok(mut movement) = movements.get_mut(entity) {
+            movement.apply_force(PartialForce {
+                id: Avoidance::FORCE_ID.to_string(),
+                force: Some(force),
+                active: Some(true),
+                group: Some(Avoidance::GROUP),
+                ..Default::default()
+            });
+        }
+    }
+
+    for entity in removed.read() {
+        if let Ok(mut movement) = movements.get_mut(entity) {
+            movement.forces.remove(Avoidance::FORCE_ID);
+        }
+    }
+}
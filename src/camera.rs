@@ -0,0 +1,69 @@
+use crate::{
+    units::{PhysicsPos, WorldPos},
+    *,
+};
+use bevy_ecs::prelude::*;
+use bevy_math::prelude::*;
+use bevy_time::prelude::*;
+use bevy_transform::components::Transform;
+
+#[cfg(feature = "reflect")]
+use bevy_reflect::prelude::*;
+
+/// Makes a camera follow where `target` is drawn, including interpolation and `RenderOffset`.
+///
+/// The camera moves right after `Transform` is synced with `Position`, so it never lags a frame
+/// behind the body it tracks.
+#[derive(Component, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+pub struct PhysicsCameraFollow {
+    pub target: Entity,
+    /// Half extents in tiles of the area around the camera center the target can move in
+    /// without moving the camera
+    pub deadzone: Vec2,
+    /// How quickly the camera catches up, per second. `0.0` snaps to the target immediately.
+    pub smoothing: f32,
+}
+
+impl PhysicsCameraFollow {
+    pub fn new(target: Entity) -> Self {
+        Self {
+            target,
+            deadzone: Vec2::ZERO,
+            smoothing: 0.0,
+        }
+    }
+}
+
+pub(crate) fn follow_camera_target(
+    mut cameras: Query<(&PhysicsCameraFollow, &mut Transform)>,
+    targets: Query<&Transform, Without<PhysicsCameraFollow>>,
+    tile_size: Res<TileSize>,
+    time: Res<Time>,
+) {
+    for (follow, mut transf) in &mut cameras {
+        let Ok(target) = targets.get(follow.target) else {
+            continue;
+        };
+
+        let camera = tile_size.to_physics(WorldPos::from_translation(transf.translation));
+        let target = tile_size.to_physics(WorldPos::from_translation(target.translation));
+        let offset = target.0 - camera.0;
+
+        // only move as far as needed to bring the target back inside the deadzone
+        let excess = offset - offset.clamp(-follow.deadzone, follow.deadzone);
+        if excess == Vec2::ZERO {
+            continue;
+        }
+
+        let t = if follow.smoothing > 0.0 {
+            1.0 - (-follow.smoothing * time.delta_secs()).exp()
+        } else {
+            1.0
+        };
+
+        let new_camera = tile_size.to_world(PhysicsPos(camera.0 + excess * t));
+        transf.translation = new_camera.extend(transf.translation.z);
+    }
+}
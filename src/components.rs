@@ -33,11 +33,34 @@ pub struct Movement {
     /// Use `apply_force()` to add a force. Remove forces directly with `HashMap.remove()`
     pub forces: HashMap<String, Force>,
     /// Scalar by which `Force`s that are inactive will be damped with.
+    ///
+    /// Each axis is damped independently: `x` only reduces the horizontal component of a force
+    /// and `y` only the vertical one, e.g. `vec2(8.0, 0.0)` for a side-scroller that stops quickly
+    /// on the ground but keeps its vertical speed.
+    ///
+    /// How damping is applied, see `DampingMode`.
     pub damping: Vec2,
+    pub damping_mode: DampingMode,
+    /// Friction of the static surfaces touched during the last tick, added to `damping`.
+    ///
+    /// Set by the solver from the `Friction` of static colliders.
+    pub friction: Vec2,
+    /// Force groups that are currently ignored when calculating the velocity.
+    ///
+    /// Use `pause_group()` and `resume_group()` to change.
+    pub paused_groups: Vec<ForceGroup>,
 }
 
 impl Movement {
+    /// Maximum length of the sum of all forces, in tiles per second.
     pub const MAX_VELOCITY: f32 = 256.0;
+    /// Id of the force used by `reflect_velocity()`.
+    pub const REFLECT_FORCE: &str = "reflect";
+    /// Id of the force used by `KnockbackExt::apply_knockback()`.
+    pub const KNOCKBACK_FORCE: &str = "knockback";
+    /// Rate per second at which `Movement::KNOCKBACK_FORCE` wears off on its own, on top of
+    /// `damping`, so knockback stops even on bodies without damping.
+    pub const KNOCKBACK_DECAY: f32 = 4.0;
 
     pub fn damped(damping: Vec2) -> Self {
         Self {
@@ -56,6 +79,84 @@ impl Movement {
 
         self.forces.insert(id, new_force);
     }
+
+    /// Bounces the velocity off a surface, e.g. after a `CollisionMessage`.
+    ///
+    /// `normal` points away from the surface, towards this body. The part of the velocity moving
+    /// into the surface is reversed and scaled by `restitution`, `1.0` for a perfect bounce and
+    /// `0.0` to only stop. The change is stored in the inactive force `Movement::REFLECT_FORCE`,
+    /// which is damped like any other inactive force.
+    pub fn reflect_velocity(&mut self, normal: Vec2, restitution: f32) {
+        let normal = normal.normalize_or_zero();
+        let velocity: Vec2 = self
+            .forces
+            .values()
+            .filter(|force| !self.paused_groups.contains(&force.group))
+            .map(|force| force.force)
+            .sum();
+
+        let into_surface = velocity.dot(normal);
+        if into_surface >= 0.0 {
+            return;
+        }
+
+        let previous = self
+            .forces
+            .get(Self::REFLECT_FORCE)
+            .map_or(Vec2::ZERO, |force| force.force);
+
+        self.apply_force(PartialForce {
+            id: Self::REFLECT_FORCE.to_owned(),
+            force: Some(previous - normal * into_surface * (1.0 + restitution.max(0.0))),
+            active: Some(false),
+            ..Default::default()
+        });
+    }
+
+    /// Stops all forces in `group` from affecting the velocity until `resume_group()` is called.
+    ///
+    /// Paused forces are neither applied nor damped.
+    pub fn pause_group(&mut self, group: ForceGroup) {
+        if !self.paused_groups.contains(&group) {
+            self.paused_groups.push(group);
+        }
+    }
+
+    pub fn resume_group(&mut self, group: ForceGroup) {
+        self.paused_groups.retain(|paused| *paused != group);
+    }
+
+    pub fn is_group_paused(&self, group: ForceGroup) -> bool {
+        self.paused_groups.contains(&group)
+    }
+
+    /// Removes all forces in `group`.
+    pub fn clear_group(&mut self, group: ForceGroup) {
+        self.forces.retain(|_, force| force.group != group);
+    }
+
+    /// Estimated heap size of the forces and paused groups in bytes, e.g. to find bodies whose
+    /// forces are added under new ids every tick but never removed.
+    pub fn memory_usage(&self) -> usize {
+        let ids: usize = self
+            .forces
+            .iter()
+            .map(|(key, force)| key.capacity() + force.id.capacity())
+            .sum();
+
+        hash_table_bytes::<String, Force>(self.forces.capacity())
+            + ids
+            + self.paused_groups.capacity() * size_of::<ForceGroup>()
+    }
+
+    /// Multiplies all forces in `group` by `scale`.
+    pub fn scale_group(&mut self, group: ForceGroup, scale: Vec2) {
+        for force in self.forces.values_mut() {
+            if force.group == group {
+                force.force *= scale;
+            }
+        }
+    }
 }
 
 /// Collider represented by a rectangle with rounded corners
@@ -63,6 +164,10 @@ impl Movement {
 #[cfg_attr(feature = "reflect", derive(Reflect))]
 #[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
 #[cfg_attr(feature = "reflect", reflect(Component))]
+#[cfg_attr(
+    all(feature = "physics", feature = "log"),
+    component(on_add = crate::warn_without_plugin)
+)]
 pub struct Collider {
     pub size: Vec2,
     pub radius: f32,
@@ -72,17 +177,29 @@ pub struct Collider {
 impl Collider {
     pub const DEFAULT_RADIUS: f32 = 0.2;
 
+    /// Creates a collider, clamping the radius so that it fits inside `size` and repairing other
+    /// invalid values, see `clamped()`.
+    ///
+    /// Use `try_new()` to get an error instead of clamping.
     pub const fn new(size: Vec2, radius: f32, ctype: ColliderType) -> Self {
-        let diameter = radius * 2.0;
-
-        debug_assert!(size.x >= diameter);
-        debug_assert!(size.y >= diameter);
-
         Self {
             size,
             radius,
             ctype,
         }
+        .clamped()
+    }
+
+    /// Creates a collider, returning an error if the parameters are invalid.
+    pub fn try_new(size: Vec2, radius: f32, ctype: ColliderType) -> Result<Self, ColliderError> {
+        let collider = Self {
+            size,
+            radius,
+            ctype,
+        };
+
+        collider.validate()?;
+        Ok(collider)
     }
 
     pub const fn rect(size: Vec2, ctype: ColliderType) -> Self {
@@ -91,6 +208,7 @@ impl Collider {
             radius: 0.0,
             ctype,
         }
+        .clamped()
     }
 
     pub const fn circle(radius: f32, ctype: ColliderType) -> Self {
@@ -99,6 +217,239 @@ impl Collider {
             radius,
             ctype,
         }
+        .clamped()
+    }
+
+    /// Returns a `ColliderTween` that grows or shrinks this collider to `size` over `duration`
+    /// seconds. Insert it on the entity to start resizing.
+    pub fn resize_to(&self, size: Vec2, duration: f32) -> ColliderTween {
+        ColliderTween {
+            from_size: self.size,
+            to_size: size,
+            from_radius: self.radius,
+            to_radius: self.radius,
+            duration,
+            elapsed: 0.0,
+        }
+    }
+
+    /// Checks that the size and radius describe a valid rounded rectangle
+    /// and that a dynamic mass is usable by the solver.
+    pub fn validate(&self) -> Result<(), ColliderError> {
+        if !self.size.is_finite() || self.size.x < 0.0 || self.size.y < 0.0 {
+            return Err(ColliderError::InvalidSize(self.size));
+        }
+
+        if !self.radius.is_finite() || self.radius < 0.0 {
+            return Err(ColliderError::InvalidRadius(self.radius));
+        }
+
+        if self.radius * 2.0 > self.size.min_element() {
+            return Err(ColliderError::RadiusTooLarge {
+                size: self.size,
+                radius: self.radius,
+            });
+        }
+
+        if let ColliderType::Dynamic(mass) = self.ctype
+            && (!mass.is_finite() || mass <= 0.0)
+        {
+            return Err(ColliderError::InvalidMass(mass));
+        }
+
+        Ok(())
+    }
+
+    /// Returns a copy with a different size, shrinking the radius if needed.
+    pub const fn with_size(self, size: Vec2) -> Self {
+        Self { size, ..self }.clamped()
+    }
+
+    /// Returns a copy with a different corner radius, shrunk to fit inside the size.
+    pub const fn with_radius(self, radius: f32) -> Self {
+        Self { radius, ..self }.clamped()
+    }
+
+    /// Returns a copy that is a dynamic collider with the given mass.
+    pub const fn with_mass(self, mass: f32) -> Self {
+        Self {
+            ctype: ColliderType::Dynamic(mass),
+            ..self
+        }
+    }
+
+    /// Returns the minimum translation vector needed to separate `other` from `self`,
+    /// or `None` if the colliders do not overlap.
+    ///
+    /// The vector points from `self` towards `other`. When the centers are aligned on an axis
+    /// the vector points towards positive x or y.
+    ///
+    /// ```
+    /// # use bevy_math::prelude::*;
+    /// # use pvw_rrect_physics::*;
+    /// let circle = Collider::circle(0.5, ColliderType::Static);
+    ///
+    /// // directly above: pushed straight up, without any sideways component
+    /// let mtv = circle.penetration(Vec2::ZERO, &circle, vec2(0.0, 0.75)).unwrap();
+    /// assert_eq!(mtv, vec2(0.0, 0.25));
+    ///
+    /// // same center: still a finite push
+    /// let mtv = circle.penetration(Vec2::ZERO, &circle, Vec2::ZERO).unwrap();
+    /// assert_eq!(mtv, vec2(0.0, 1.0));
+    ///
+    /// // diagonal against a rounded corner: pushed along the diagonal
+    /// let rounded = Collider::new(Vec2::ONE, 0.25, ColliderType::Static);
+    /// let mtv = rounded.penetration(Vec2::ZERO, &rounded, vec2(-0.8, -0.8)).unwrap();
+    /// assert!(mtv.x < 0.0 && (mtv.x - mtv.y).abs() < 1e-6);
+    ///
+    /// // touching is not overlapping
+    /// assert!(circle.penetration(Vec2::ZERO, &circle, vec2(1.0, 0.0)).is_none());
+    /// ```
+    pub fn penetration(&self, pos: Vec2, other: &Collider, other_pos: Vec2) -> Option<Vec2> {
+        let offset = other_pos - pos;
+
+        // the colliders overlap when the center of `other` is inside the sum of both shapes,
+        // a rounded rectangle with the inner rectangles and the radii of both added together
+        let radii = self.radius + other.radius;
+        let inner_half = (self.size + other.size) * 0.5 - radii;
+        let q = offset.abs() - inner_half;
+
+        let sign = vec2(
+            if offset.x < 0.0 { -1.0 } else { 1.0 },
+            if offset.y < 0.0 { -1.0 } else { 1.0 },
+        );
+
+        if q.x > 0.0 && q.y > 0.0 {
+            // closest point is on a rounded corner
+            let dist = q.length();
+            if dist >= radii {
+                return None;
+            }
+
+            Some(q / dist * (radii - dist) * sign)
+        } else {
+            // closest point is on a straight edge
+            let overlap = radii - q;
+            if overlap.x <= 0.0 || overlap.y <= 0.0 {
+                return None;
+            }
+
+            if overlap.x < overlap.y {
+                Some(Vec2::new(overlap.x * sign.x, 0.0))
+            } else {
+                Some(Vec2::new(0.0, overlap.y * sign.y))
+            }
+        }
+    }
+
+    /// Returns true if `other` lies entirely inside `self`.
+    pub fn contains(&self, pos: Vec2, other: &Collider, other_pos: Vec2) -> bool {
+        // `other` is its inner rect grown by its radius, and the distance to a convex shape is
+        // convex, so checking the corners of the inner rect is enough
+        let inner_half = other.size * 0.5 - other.radius;
+        [
+            vec2(-inner_half.x, -inner_half.y),
+            vec2(inner_half.x, -inner_half.y),
+            vec2(-inner_half.x, inner_half.y),
+            vec2(inner_half.x, inner_half.y),
+        ]
+        .into_iter()
+        .all(|corner| self.sdf(pos, other_pos + corner) <= -other.radius)
+    }
+
+    /// Area of the rounded rectangle in square tiles.
+    pub fn area(&self) -> f32 {
+        self.size.x * self.size.y - (4.0 - std::f32::consts::PI) * self.radius * self.radius
+    }
+
+    /// Returns the fraction of `other` that overlaps `self`, from 0 (apart) to 1 (contained).
+    ///
+    /// Rounded corners are ignored, so this is the overlapping area of the bounding rectangles.
+    pub fn overlap_fraction(&self, pos: Vec2, other: &Collider, other_pos: Vec2) -> f32 {
+        let rect = Rect::from_center_size(pos, self.size);
+        let other_rect = Rect::from_center_size(other_pos, other.size);
+
+        let other_area = other_rect.width() * other_rect.height();
+        if other_area <= 0.0 {
+            return if rect.contains(other_pos) { 1.0 } else { 0.0 };
+        }
+
+        let intersection = rect.intersect(other_rect);
+        if intersection.is_empty() {
+            return 0.0;
+        }
+
+        intersection.width() * intersection.height() / other_area
+    }
+
+    /// Signed distance from `point` to the surface of the collider at `pos`, negative inside.
+    ///
+    /// Exact for the rounded shape, so it can be used beyond collisions, e.g. to keep AI away
+    /// from walls or to pass the shapes to a shader:
+    ///
+    /// ```
+    /// # use bevy_math::prelude::*;
+    /// # use pvw_rrect_physics::prelude::*;
+    /// let wall = Collider::new(vec2(2.0, 2.0), 0.5, ColliderType::Static);
+    /// let point = vec2(3.0, 0.0);
+    ///
+    /// assert_eq!(wall.sdf(Vec2::ZERO, point), 2.0);
+    /// assert_eq!(wall.gradient(Vec2::ZERO, point), Vec2::X);
+    ///
+    /// // the closest point on the surface
+    /// let surface = point - wall.gradient(Vec2::ZERO, point) * wall.sdf(Vec2::ZERO, point);
+    /// assert_eq!(surface, vec2(1.0, 0.0));
+    /// ```
+    pub fn sdf(&self, pos: Vec2, point: Vec2) -> f32 {
+        let q = (point - pos).abs() - (self.size * 0.5 - self.radius);
+        q.max(Vec2::ZERO).length() + q.max_element().min(0.0) - self.radius
+    }
+
+    /// Direction in which `sdf()` grows fastest at `point`, a unit vector pointing away from the
+    /// closest point on the surface of the collider at `pos`.
+    pub fn gradient(&self, pos: Vec2, point: Vec2) -> Vec2 {
+        let delta = point - pos;
+        let q = delta.abs() - (self.size * 0.5 - self.radius);
+
+        let gradient = if q.max_element() > 0.0 {
+            // outside the inner rect, towards the closest point on it
+            q.max(Vec2::ZERO).normalize_or_zero()
+        } else if q.x > q.y {
+            Vec2::X
+        } else {
+            Vec2::Y
+        };
+
+        gradient * delta.signum()
+    }
+
+    /// Returns a copy that passes `validate()`: negative or non-finite sizes and radii are set to
+    /// zero, the radius is shrunk to fit inside the size and a dynamic mass that is not positive
+    /// and finite is set to 1.
+    pub const fn clamped(self) -> Self {
+        const fn non_negative(value: f32) -> f32 {
+            if value.is_finite() {
+                value.max(0.0)
+            } else {
+                0.0
+            }
+        }
+
+        let size = Vec2::new(non_negative(self.size.x), non_negative(self.size.y));
+        let max_radius = size.x.min(size.y) * 0.5;
+
+        let ctype = match self.ctype {
+            ColliderType::Dynamic(mass) if !mass.is_finite() || mass <= 0.0 => {
+                ColliderType::Dynamic(1.0)
+            },
+            ctype => ctype,
+        };
+
+        Self {
+            size,
+            radius: non_negative(self.radius).min(max_radius),
+            ctype,
+        }
     }
 }
 
@@ -108,6 +459,686 @@ impl Default for Collider {
     }
 }
 
+/// Overrides mass when two dynamic colliders push each other.
+///
+/// The body with the lower priority absorbs the whole correction regardless of mass.
+/// Bodies with equal priority share it based on mass. Entities without this component
+/// have a priority of 0.
+#[derive(Component, Default, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+pub struct PushPriority(pub i32);
+
+/// Marks a static collider as a moving platform.
+///
+/// Dynamic bodies standing on top of it are moved along with it each tick,
+/// whether it is moved by its own forces or by setting its `Position` directly.
+#[derive(Component, Default, Clone, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+pub struct Platform {
+    /// Displacement of the platform during the last physics tick.
+    pub displacement: Vec2,
+    #[cfg(feature = "physics")]
+    pub(crate) last_position: Option<Vec2>,
+    #[cfg(feature = "physics")]
+    #[cfg_attr(feature = "reflect", reflect(ignore))]
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    pub(crate) riders: Vec<Entity>,
+}
+
+/// Anisotropic friction of a static collider.
+///
+/// Dynamic bodies touching the collider have their inactive forces damped by this amount on top
+/// of their own `Movement::damping`, separately along each axis.
+#[derive(Component, Default, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+pub struct Friction(pub Vec2);
+
+/// Game specific id of a collider, e.g. a tile coordinate or a network id.
+///
+/// It is copied into the `ContactData` of every collision the entity takes part in,
+/// so integrations do not need an extra lookup to identify what was hit.
+#[derive(Component, Default, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+pub struct UserData(pub u128);
+
+/// Allows a static collider to be split into smaller pieces by `damage_region()`.
+#[derive(Component, Default, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+pub struct Destructible {
+    /// Pieces that are thinner than this along either axis are removed
+    pub min_size: f32,
+}
+
+/// Damages whatever collides with this entity hard enough, e.g. spikes or a crusher.
+///
+/// A `DamageMessage` is sent for the other entity of every collision where the two bodies move
+/// towards each other at `min_impact` tiles per second or more, at most once every `cooldown`
+/// seconds per pair. Use a `min_impact` of zero to hurt on any touch.
+#[derive(Component, Default, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+pub struct ContactDamage {
+    pub amount: f32,
+    pub min_impact: f32,
+    pub cooldown: f32,
+}
+
+/// Makes a static collider look for contacts itself, like a dynamic body does.
+///
+/// Static colliders are normally skipped by the broadphase, so their collisions are only found
+/// from the dynamic side and pairs of static colliders are never reported. Add this to a trap or
+/// trigger that has to know what touched it, including other static colliders. The static
+/// collider is never moved by these contacts.
+#[derive(Component, Default, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+pub struct ActiveStatic;
+
+/// Which peer simulates an entity in a networked game. Entities without it are simulated by the server.
+///
+/// Apps with a `LocalClient` resource are clients, every other app is the server. Bodies the
+/// app has no authority over are not integrated, are never moved by the local solver and are
+/// only synchronized from the network. They still push the local bodies like a kinematic body,
+/// e.g. a player simulated by its client pushes the server's NPCs out of the way.
+#[derive(Component, Default, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+pub enum Authority {
+    /// Simulated by the server, e.g. NPCs (default)
+    #[default]
+    Server,
+    /// Simulated by the client with this id, e.g. its own player
+    Client(u64),
+    /// Simulated by everyone, e.g. predicted projectiles
+    Shared,
+}
+
+impl Authority {
+    /// Returns true if the app with the given `LocalClient` id, `None` for the server,
+    /// simulates the entity.
+    pub fn is_local(&self, local_client: Option<u64>) -> bool {
+        match self {
+            Self::Server => local_client.is_none(),
+            Self::Client(id) => local_client == Some(*id),
+            Self::Shared => true,
+        }
+    }
+}
+
+/// Offset between where a body this client predicts is drawn and its `Position`, left over from
+/// a server correction. See `LocalClient::correction_window`.
+///
+/// Added and removed by the client plugin whenever `Position` changed between two physics ticks,
+/// e.g. because the server's position was replicated. `Transform` follows `Position` plus
+/// `offset`, which shrinks to zero over the remaining seconds, so large corrections are caught up
+/// in the same time as small ones. Teleporting a predicted body counts as a correction as well.
+#[derive(Component, Default, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+pub struct PredictionError {
+    pub offset: Vec2,
+    pub remaining: f32,
+}
+
+/// Marks an entity whose `Transform` jumps straight to its `Position` every frame, ignoring
+/// `PhysicsConfig::interpolation` and `LocalClient::remote_interpolation`.
+///
+/// For things that must never lag behind, like cursors, effects that teleport or objects locked
+/// to the camera.
+#[derive(Component, Default, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+pub struct NoInterpolation;
+
+/// Offset in tiles from `Position` to where the entity is drawn, e.g. for a sprite whose
+/// visual center is above its feet collider.
+///
+/// Only `Transform` is moved, the collider stays at `Position`. With `SyncMode::FromTransform`
+/// the offset is subtracted again.
+#[derive(Component, Default, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+pub struct RenderOffset(pub Vec2);
+
+/// Which of `Position` and `Transform` drives the other.
+///
+/// Entities animated by other crates, e.g. tweening or skeletal animation, can use
+/// `SyncMode::FromTransform` so their collider follows the animation. Such entities should have a
+/// static or kinematic collider, since any push from the solver is overwritten on the next tick.
+#[derive(Component, Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+pub enum SyncMode {
+    /// `Transform` follows `Position` (default)
+    #[default]
+    ToTransform,
+    /// `Position` is set from `Transform` at the start of every physics tick
+    FromTransform,
+}
+
+/// How `update_translation` sets the z of `Transform`, e.g. to y-sort characters in a top-down game.
+///
+/// The z is derived from `Position` rather than the interpolated `Transform`, so draw order
+/// changes on the same tick as the physics and does not flicker. Entities without it keep their z.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+pub enum ZIndexMode {
+    /// Always this z
+    Fixed(f32),
+    /// `base - y * scale`, so bodies lower on the screen are drawn in front
+    YSort { base: f32, scale: f32 },
+}
+
+impl ZIndexMode {
+    /// Returns the z of an entity at `pos`.
+    pub fn z(&self, pos: Vec2) -> f32 {
+        match self {
+            Self::Fixed(z) => *z,
+            Self::YSort { base, scale } => base - pos.y * scale,
+        }
+    }
+}
+
+/// Mass per square tile of a dynamic collider.
+///
+/// The mass of `ColliderType::Dynamic` is set to `Collider::area()` times the density whenever the
+/// collider or the density changes, so bodies of different sizes push each other consistently.
+/// Static colliders and sensors are not affected.
+#[derive(Component, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+pub struct Density(pub f32);
+
+impl Default for Density {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Marks an entity, usually a player, around which bodies are simulated at full rate.
+///
+/// See `PhysicsConfig::lod`.
+#[derive(Component, Default, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+pub struct SimulationAnchor;
+
+/// Makes a body skip collision detection entirely while still integrating its forces, e.g. birds
+/// or debris in the background.
+///
+/// Added and removed automatically by distance with `SimulationLod::simplified_distance`. When
+/// the marker is removed, the body is immediately pushed out of any static collider it ended up in.
+#[derive(Component, Default, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+pub struct SimplifiedPhysics;
+
+/// Stops the contacts of a collider from sending `CollisionMessage`, e.g. for debris landing on
+/// the floor that nothing listens to. The contacts are still resolved.
+///
+/// A contact is silent if either collider is. Everything built from `CollisionMessage` skips
+/// it as well:
+/// - `Contacts` and `ContactCount`
+/// - `FrameCollisionMessage`
+/// - contact damage and `HitboxMessage`
+/// - `SensorOverlapStarted`, `SensorOverlapEnded`, `GroupOverlapStarted` and `GroupOverlapEnded`,
+///   so a silent sensor or a silent body never triggers sensors
+///
+/// Movement is unaffected, e.g. knockback still stops at silent walls.
+#[derive(Component, Default, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+pub struct Silent;
+
+/// Opts an entity into having its contacts counted in `ContactCount`.
+#[derive(Component, Default, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+#[require(ContactCount)]
+pub struct TrackContacts;
+
+/// Number of colliders touching the entity during the last physics tick.
+///
+/// Only updated for entities with `TrackContacts`.
+#[derive(Component, Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+pub struct ContactCount(pub u32);
+
+/// Distance the entity actually moved during the last physics tick, after collisions were resolved.
+///
+/// Unlike `Movement::velocity`, this is zero while walking into a wall, so it suits walk animations,
+/// footstep sounds and network delta compression. Only updated for entities that have it.
+#[derive(Component, Default, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+#[cfg_attr(feature = "physics", require(TickStart))]
+pub struct LinearDisplacement(pub Vec2);
+
+/// `Position` before the current tick moved the entity, used for `LinearDisplacement`.
+#[cfg(feature = "physics")]
+#[derive(Component, Default, Clone, Copy, Debug)]
+pub(crate) struct TickStart(pub(crate) Vec2);
+
+/// Makes a sensor collider part of a named trigger made of several sensors.
+///
+/// `GroupOverlapStarted` and `GroupOverlapEnded` are sent when an entity starts or stops
+/// overlapping any sensor of the group, so moving between two sensors of the same group
+/// does not trigger anything.
+#[derive(Component, Default, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+pub struct SensorGroup(pub String);
+
+/// Maximum height a dynamic body can step up onto when moving sideways into a static collider.
+///
+/// If the top of the static collider is at most this far above the bottom of the body,
+/// the body is lifted on top of it instead of being blocked, e.g. for stairs or small ledges.
+#[derive(Component, Default, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+pub struct StepUp(pub f32);
+
+/// Fraction of the motion into a static collider that is turned into motion along it, from `0.0`
+/// to `1.0`.
+///
+/// A body moving diagonally into a wall normally only keeps the part of its motion along the wall.
+/// With `1.0` it keeps its whole speed, so it slides smoothly around obstacles, e.g. for the
+/// player of a top-down game. Moving straight into a wall still stops the body.
+#[derive(Component, Default, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+pub struct WallSlide(pub f32);
+
+/// Expands the broadphase bounds of an entity ahead of it by this many ticks of its velocity.
+///
+/// Fast bodies like projectiles then find the colliders in their path a tick early, without a
+/// larger cell size for everyone. Only the broadphase uses the margin, contacts are still found
+/// with the real collider.
+#[derive(Component, Default, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+pub struct SpeculativeMargin(pub f32);
+
+/// Keeps the distance between the entities `a` and `b` within `min_length..=max_length`.
+///
+/// Spawn it on its own entity, so a body can have any number of joints. Solved after collisions
+/// by moving both entities based on their mass. Static colliders and entities without a dynamic
+/// collider are not moved, so they act as anchors.
+///
+/// The joint is despawned with a `JointBroken` message when either entity is despawned.
+#[derive(Component, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+pub struct DistanceJoint {
+    pub a: Entity,
+    pub b: Entity,
+    pub min_length: f32,
+    pub max_length: f32,
+}
+
+impl DistanceJoint {
+    /// A joint that only stops the entities from moving further apart than `length`.
+    pub fn rope(a: Entity, b: Entity, length: f32) -> Self {
+        Self {
+            a,
+            b,
+            min_length: 0.0,
+            max_length: length,
+        }
+    }
+
+    /// A joint that keeps the entities exactly `length` apart.
+    pub fn rod(a: Entity, b: Entity, length: f32) -> Self {
+        Self {
+            a,
+            b,
+            min_length: length,
+            max_length: length,
+        }
+    }
+}
+
+/// Resizes the `Collider` of an entity over time, e.g. for a growing boss hitbox.
+///
+/// Created with `Collider::resize_to()`. The radius is shrunk whenever it does not fit inside the
+/// size, so the collider stays valid on every tick. The component is removed once the tween is done.
+#[derive(Component, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+pub struct ColliderTween {
+    pub from_size: Vec2,
+    pub to_size: Vec2,
+    pub from_radius: f32,
+    pub to_radius: f32,
+    /// Length of the tween in seconds
+    pub duration: f32,
+    /// Seconds since the tween started
+    pub elapsed: f32,
+}
+
+impl ColliderTween {
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.to_radius = radius;
+        self
+    }
+
+    /// Progress from `0.0` at the start to `1.0` when done.
+    pub fn progress(&self) -> f32 {
+        if self.duration > 0.0 {
+            (self.elapsed / self.duration).clamp(0.0, 1.0)
+        } else {
+            1.0
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.progress() >= 1.0
+    }
+
+    /// Returns `collider` with the size and radius at the current progress.
+    pub fn apply(&self, collider: Collider) -> Collider {
+        let t = self.progress();
+        Collider {
+            size: self.from_size.lerp(self.to_size, t),
+            radius: self.from_radius.lerp(self.to_radius, t),
+            ctype: collider.ctype,
+        }
+        .clamped()
+    }
+}
+
+/// Keeps a body within `max_length` of an anchor, e.g. for a grappling hook or a leashed pet.
+///
+/// Solved after collisions, so the length limit holds even when the body is pushed. Only the
+/// tethered body is moved, use a `DistanceJoint` to pull the anchor as well. Beyond
+/// `SPRING_START` of the length, the body is also pulled towards the anchor with a force of
+/// `stiffness` per tile, which softens the stop at the edge.
+#[derive(Component, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+#[require(Position)]
+pub struct TetherConstraint {
+    pub anchor: TetherAnchor,
+    pub max_length: f32,
+    pub stiffness: f32,
+}
+
+impl TetherConstraint {
+    pub const FORCE_ID: &str = "tether";
+    pub const GROUP: ForceGroup = ForceGroup::Environment;
+    /// Fraction of `max_length` from which the spring force pulls the body back
+    pub const SPRING_START: f32 = 0.8;
+
+    /// A tether with a hard length limit only.
+    pub fn new(anchor: TetherAnchor, max_length: f32) -> Self {
+        Self {
+            anchor,
+            max_length,
+            stiffness: 0.0,
+        }
+    }
+
+    pub fn with_stiffness(mut self, stiffness: f32) -> Self {
+        self.stiffness = stiffness;
+        self
+    }
+}
+
+/// What a `TetherConstraint` is tied to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+pub enum TetherAnchor {
+    Point(Vec2),
+    /// The `Position` of an entity. The tether is ignored while the entity has none.
+    Entity(Entity),
+}
+
+/// Pulls a body towards `rest_offset` from a target entity like a spring, e.g. a drone or lantern
+/// following the player.
+///
+/// Unlike `WeldedTo`, the follower is moved by forces, so it lags behind, overshoots and collides
+/// with the world on the way. `stiffness` is the acceleration per tile away from the rest position
+/// and `damping` the acceleration per tile per second of speed relative to the target. The spring
+/// is ignored while the target has no `Position`.
+#[derive(Component, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+#[require(Position)]
+pub struct SpringFollower {
+    pub target: Entity,
+    pub rest_offset: Vec2,
+    pub stiffness: f32,
+    pub damping: f32,
+}
+
+impl SpringFollower {
+    pub const FORCE_ID: &str = "spring_follower";
+    pub const GROUP: ForceGroup = ForceGroup::AI;
+}
+
+/// Rigidly attaches an entity to another entity after collisions are resolved.
+///
+/// The entity keeps its own collider for hit detection but its `Position` always equals
+/// the parent's `Position` plus `offset`. Use a sensor collider to avoid pushing the parent.
+#[derive(Component, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+#[require(Position)]
+pub struct WeldedTo {
+    pub parent: Entity,
+    pub offset: Vec2,
+}
+
+/// Marks a sensor welded to a character as one of its hitboxes or hurtboxes, e.g. the fist and
+/// body of a fighting game character.
+///
+/// Place it with `WeldedTo`, the parent is the owner. Unlike other welded entities, hitboxes are
+/// moved to their owner before collisions are checked, so their overlaps match the owner's
+/// current position. A `HitboxMessage` is sent for every hitbox overlapping a hurtbox of another
+/// owner.
+#[derive(Component, Default, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+#[require(Position, Collider = Collider::rect(Vec2::ONE, ColliderType::Sensor))]
+pub enum HitboxKind {
+    /// Deals hits, e.g. a sword swing
+    #[default]
+    Hitbox,
+    /// Takes hits, e.g. the body
+    Hurtbox,
+}
+
+/// Makes an entity part of a rigid body built from several entities, e.g. a vehicle made of tiles.
+///
+/// The `Movement` of `root` moves the whole group, and a collision correction on any member
+/// moves every member together, so the group never shears apart. Each member stays at `offset`
+/// from the root's `Position`. The root does not need a collider itself.
+///
+/// Collisions report the member that was hit, with the root in `ContactData::group`, so each
+/// member can act as a part of a compound shape.
+#[derive(Component, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+#[require(Position)]
+pub struct RigidGroup {
+    pub root: Entity,
+    pub offset: Vec2,
+}
+
+/// Applies the same force to an entity every tick, e.g. gravity or wind.
+#[derive(Component, Default, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+#[require(Position)]
+pub struct ConstantForce(pub Vec2);
+
+impl ConstantForce {
+    pub const FORCE_ID: &str = "constant_force";
+    pub const GROUP: ForceGroup = ForceGroup::Environment;
+}
+
+/// Applies a force towards another entity every tick.
+#[derive(Component, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+#[require(Position)]
+pub struct SeekTarget {
+    pub entity: Entity,
+    pub strength: f32,
+}
+
+impl SeekTarget {
+    pub const FORCE_ID: &str = "seek_target";
+    pub const GROUP: ForceGroup = ForceGroup::AI;
+}
+
+/// Applies a force away from a point every tick.
+#[derive(Component, Default, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+#[require(Position)]
+pub struct FleePoint {
+    pub point: Vec2,
+    pub strength: f32,
+}
+
+impl FleePoint {
+    pub const FORCE_ID: &str = "flee_point";
+    pub const GROUP: ForceGroup = ForceGroup::AI;
+}
+
+/// Steers away from nearby dynamic bodies before they collide, so crowds spread out smoothly
+/// instead of popping apart on contact.
+///
+/// Every dynamic body within `radius` tiles of the position pushes this one away, from
+/// `strength` when touching down to zero at `radius`. Static colliders are left to the solver.
+#[derive(Component, Default, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+#[require(Position)]
+pub struct Avoidance {
+    pub radius: f32,
+    pub strength: f32,
+}
+
+impl Avoidance {
+    pub const FORCE_ID: &str = "avoidance";
+    pub const GROUP: ForceGroup = ForceGroup::AI;
+}
+
+/// Tag for enabling or disabling the physics of many entities at once, e.g. every body on a
+/// dungeon floor the player left. See `PhysicsGroups`.
+#[derive(Component, Default, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+pub struct PhysicsGroup(pub u32);
+
+/// Marks a dynamic physics body.
+///
+/// Adds a 1x1 `Collider` with a mass of 1 if the entity has none, as well as `Position` and `Movement`.
+/// Override the collider with e.g. `Collider::default().with_size(size).with_mass(mass)`.
+#[derive(Component, Default, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+#[require(Position, Collider = Collider::default().with_mass(1.0))]
+pub struct DynamicBody;
+
+/// Marks a static physics body.
+///
+/// Adds a 1x1 static `Collider` without rounded corners if the entity has none,
+/// as well as `Position` and `Movement`.
+#[derive(Component, Default, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+#[require(Position, Collider = Collider::rect(Vec2::ONE, ColliderType::Static))]
+pub struct StaticBody;
+
+/// Marks a sensor that detects overlaps without collision response.
+///
+/// Adds a 1x1 sensor `Collider` if the entity has none, as well as `Position` and `Movement`.
+#[derive(Component, Default, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+#[require(Position, Collider = Collider::rect(Vec2::ONE, ColliderType::Sensor))]
+pub struct SensorBody;
+
+/// Reason a collider was rejected by `Collider::try_new()` or `Collider::validate()`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColliderError {
+    /// Size is negative or not finite
+    InvalidSize(Vec2),
+    /// Radius is negative or not finite
+    InvalidRadius(f32),
+    /// Diameter is larger than the smallest side of the size
+    RadiusTooLarge { size: Vec2, radius: f32 },
+    /// Dynamic mass is zero, negative or not finite
+    InvalidMass(f32),
+}
+
+impl std::fmt::Display for ColliderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidSize(size) => write!(f, "invalid collider size {size}"),
+            Self::InvalidRadius(radius) => write!(f, "invalid collider radius {radius}"),
+            Self::RadiusTooLarge { size, radius } => {
+                write!(
+                    f,
+                    "collider radius {radius} does not fit inside size {size}"
+                )
+            },
+            Self::InvalidMass(mass) => write!(f, "invalid dynamic collider mass {mass}"),
+        }
+    }
+}
+
+impl std::error::Error for ColliderError {}
+
 #[derive(Default, Clone, Copy, Debug)]
 #[cfg_attr(feature = "reflect", derive(Reflect))]
 #[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
@@ -122,12 +1153,54 @@ pub enum ColliderType {
     Dynamic(f32),
 }
 
-#[derive(Clone)]
+/// How `Movement::damping` reduces inactive forces.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+pub enum DampingMode {
+    /// Forces are lerped towards zero by `damping * dt` every tick (default).
+    ///
+    /// Behaves roughly the same at any fixed tick rate, but drifts for large `damping * dt`.
+    /// Between `1.0` and `2.0` a force overshoots zero and flips back and forth while it dies out,
+    /// a force that would grow instead is stopped. Negative damping is ignored.
+    #[default]
+    Linear,
+    /// Forces are multiplied by `(1.0 - damping).powf(dt)` every tick, so `damping` is the
+    /// fraction of a force lost per second.
+    ///
+    /// Identical at any fixed tick rate. `1.0` stops a force immediately, values are clamped to `0.0..=1.0`.
+    Exponential,
+}
+
+/// Category of a `Force`, used to pause, clear or scale related forces together.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+pub enum ForceGroup {
+    /// Forces without a specific category (default)
+    #[default]
+    Other,
+    /// Forces caused by player input
+    Input,
+    /// Forces caused by AI steering
+    AI,
+    /// Forces caused by the environment, e.g. wind or conveyor belts
+    Environment,
+    /// Forces caused by scripts or cutscenes
+    Scripted,
+}
+
+#[derive(Clone, Default)]
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "reflect", reflect(Default))]
 pub struct PartialForce {
     pub id: String,
     pub force: Option<Vec2>,
     pub active: Option<bool>,
+    pub group: Option<ForceGroup>,
+    /// Sets `Force::max_magnitude` when `Some`
+    pub max_magnitude: Option<f32>,
 }
 
 #[derive(Clone, Debug)]
@@ -137,6 +1210,9 @@ pub struct Force {
     pub id: String,
     pub force: Vec2,
     pub active: bool,
+    pub group: ForceGroup,
+    /// Length the force is clamped to before it is applied
+    pub max_magnitude: Option<f32>,
 }
 
 impl Force {
@@ -147,6 +1223,8 @@ impl Force {
             id: self.id.clone(),
             force: partial.force.unwrap_or(self.force),
             active: partial.active.unwrap_or(self.active),
+            group: partial.group.unwrap_or(self.group),
+            max_magnitude: partial.max_magnitude.or(self.max_magnitude),
         }
     }
 }
@@ -157,6 +1235,8 @@ impl Default for Force {
             id: Self::DEFAULT_NAME.to_string(),
             force: Vec2::ZERO,
             active: false,
+            group: ForceGroup::default(),
+            max_magnitude: None,
         }
     }
 }
@@ -167,6 +1247,8 @@ impl From<PartialForce> for Force {
             id: value.id,
             force: value.force.unwrap_or(Vec2::ZERO),
             active: value.active.unwrap_or(false),
+            group: value.group.unwrap_or_default(),
+            max_magnitude: value.max_magnitude,
         }
     }
 }
@@ -179,6 +1261,8 @@ impl std::ops::Mul<Vec2> for Force {
             id: self.id,
             force: self.force * rhs,
             active: self.active,
+            group: self.group,
+            max_magnitude: self.max_magnitude,
         }
     }
 }
@@ -196,3 +1280,9 @@ impl std::hash::Hash for Force {
         self.id.hash(state);
     }
 }
+
+/// Estimated heap size of a hash map or set with `capacity` entries, one control byte per entry
+/// on top of the entries themselves.
+pub(crate) fn hash_table_bytes<K, V>(capacity: usize) -> usize {
+    capacity * (size_of::<K>() + size_of::<V>() + 1)
+}
@@ -0,0 +1,275 @@
+use crate::CollisionLayers;
+use bevy_ecs::prelude::*;
+use bevy_math::prelude::*;
+
+#[cfg(feature = "reflect")]
+use bevy_reflect::prelude::*;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+/// Global configuration read by the physics systems.
+#[derive(Resource, Clone, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serialize", serde(default))]
+#[cfg_attr(feature = "reflect", reflect(Resource))]
+pub struct PhysicsConfig {
+    /// What to do with non-finite positions, velocities and forces.
+    pub validate: ValidationPolicy,
+    /// Length every force is clamped to before it is applied, as a sanity check against
+    /// gameplay bugs. A `ForceClamped` message is sent when a force starts being clamped.
+    pub max_force: Option<f32>,
+    /// Reduces the tick rate of bodies far away from every `SimulationAnchor`.
+    /// Every body is simulated at full rate when `None` or when there are no anchors.
+    pub lod: Option<SimulationLod>,
+    /// Penetration in tiles that is allowed to remain after a collision is resolved.
+    ///
+    /// Only the penetration beyond this is corrected, which stops bodies resting against each
+    /// other from jittering. Collisions are still reported for any overlap.
+    pub slop: f32,
+    /// Number of positions along the tick checked for pairs moving faster relative to each other
+    /// than their combined size, so fast bodies do not pass through small ones.
+    ///
+    /// The earliest hit is used for resolution and messages. `0` or `1` only checks the end of the tick.
+    pub narrow_phase_samples: u32,
+    /// Sends a `CellChanged` message whenever the grid cells of an entity change.
+    pub cell_change_messages: bool,
+    /// Skips collision resolution and only sends a `PotentialContact` for every broadphase pair,
+    /// so a custom narrow phase can be run instead. Joints are not solved either.
+    pub broadphase_only: bool,
+    /// Maximum number of broadphase pairs checked per tick, `None` for no limit.
+    ///
+    /// When there are more, pairs with a dynamic body closest to a `SimulationAnchor` are checked
+    /// first and the rest wait for a later tick, with a `PairBudgetExceeded` message. A pair that
+    /// waited for a few ticks is checked before the others, so every pair is resolved eventually.
+    /// This keeps a sudden pile of bodies from making every following tick slower.
+    pub max_pairs: Option<usize>,
+    /// Force applied to every dynamic body, like a `ConstantForce` in `ForceGroup::Environment`
+    /// with the id `PhysicsConfig::GRAVITY_FORCE`. Zero by default for top-down games.
+    pub gravity: Vec2,
+    /// How `Transform` follows `Position` between physics ticks. Entities with `NoInterpolation`
+    /// always snap.
+    pub interpolation: Interpolation,
+    /// Rounds the translation of every `Transform` that follows a `Position` to whole pixels,
+    /// which stops pixel art from shimmering at fractional positions. Interpolation still moves
+    /// smoothly underneath.
+    pub pixel_snap: bool,
+    /// Maximum number of extra passes over nearby pairs after collisions are resolved.
+    ///
+    /// Resolving one contact can push a body into another one, like a row of boxes pushed into
+    /// a wall. Each pass resolves these at the new positions, and bodies held by a wall stop
+    /// whatever pushes them. `0` leaves the remaining overlaps to the next ticks.
+    pub solver_iterations: u32,
+    /// Shape of the world, an infinite plane or a torus where positions wrap around.
+    pub topology: WorldTopology,
+    /// Checks the broadphase pairs on the compute task pool, which pays off with thousands of
+    /// pairs per tick. Results are merged in a fixed order, so the simulation stays deterministic.
+    ///
+    /// Needs the `TaskPoolPlugin`, the pairs are checked serially without it.
+    pub parallel: bool,
+    /// Fixed-size arena that bodies are kept inside, e.g. to bounce a ball off the screen edges
+    /// without wall entities. `None` for no bounds.
+    pub arena: Option<ArenaBounds>,
+    /// Maximum number of steps `move_and_slide()` takes, each at most half the collider's smaller
+    /// side. Longer moves stop early and return the rest in `SlideResult::remaining`, so a huge
+    /// delta like a correction across the whole map cannot stall the frame.
+    pub max_slide_steps: u32,
+}
+
+impl Default for PhysicsConfig {
+    fn default() -> Self {
+        Self {
+            validate: ValidationPolicy::default(),
+            max_force: None,
+            lod: None,
+            slop: 0.005,
+            narrow_phase_samples: 4,
+            cell_change_messages: false,
+            broadphase_only: false,
+            max_pairs: None,
+            gravity: Vec2::ZERO,
+            interpolation: Interpolation::default(),
+            pixel_snap: false,
+            solver_iterations: 4,
+            topology: WorldTopology::default(),
+            parallel: false,
+            arena: None,
+            max_slide_steps: 1024,
+        }
+    }
+}
+
+impl PhysicsConfig {
+    pub const GRAVITY_FORCE: &str = "gravity";
+}
+
+/// How `Transform` follows `Position`, see `PhysicsConfig::interpolation`.
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+pub enum Interpolation {
+    /// `Transform` is set to `Position` every frame (default)
+    #[default]
+    None,
+    /// `Transform` moves this fraction of the remaining distance to `Position` every frame,
+    /// which hides the steps of a low tick rate at the cost of a little lag
+    Lerp(f32),
+}
+
+/// Edges of a fixed-size arena, see `PhysicsConfig::arena`.
+///
+/// Bodies are kept inside by their collider, static colliders are never moved.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+pub struct ArenaBounds {
+    pub min: Vec2,
+    pub max: Vec2,
+    /// Response at the left and right edges
+    pub x: BoundaryResponse,
+    /// Response at the bottom and top edges
+    pub y: BoundaryResponse,
+    /// Only bodies interacting with these layers are kept inside, so e.g. UI sensors can leave
+    pub layers: CollisionLayers,
+}
+
+impl ArenaBounds {
+    /// Arena with the same response at every edge, affecting bodies in every layer.
+    pub fn new(min: Vec2, max: Vec2, response: BoundaryResponse) -> Self {
+        Self {
+            min,
+            max,
+            x: response,
+            y: response,
+            layers: CollisionLayers::default(),
+        }
+    }
+}
+
+/// What happens to a body reaching an edge of `ArenaBounds`.
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+pub enum BoundaryResponse {
+    /// Stops at the edge, keeping the velocity along it (default)
+    #[default]
+    Slide,
+    /// Bounces off the edge, keeping this fraction of the speed
+    Bounce(f32),
+    /// Appears at the opposite edge. Unlike `WorldTopology::Torus`, bodies do not collide
+    /// across the edges.
+    Wrap,
+}
+
+/// Shape of the world, see `PhysicsConfig::topology`.
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+pub enum WorldTopology {
+    /// Infinite plane (default)
+    #[default]
+    Plane,
+    /// Positions wrap around to stay within `0..size`, like in Asteroids. Bodies near opposite
+    /// edges collide with each other as if the edges were glued together.
+    Torus { size: Vec2 },
+}
+
+impl WorldTopology {
+    /// Returns the position wrapped into the world.
+    pub fn wrap(&self, pos: Vec2) -> Vec2 {
+        match self {
+            Self::Plane => pos,
+            Self::Torus { size } => pos.rem_euclid(*size),
+        }
+    }
+
+    /// Returns the shortest vector from `from` to `to`, which may cross the edges of a torus.
+    pub fn delta(&self, from: Vec2, to: Vec2) -> Vec2 {
+        let delta = to - from;
+        match self {
+            Self::Plane => delta,
+            Self::Torus { size } => delta - *size * (delta / *size).round(),
+        }
+    }
+}
+
+/// Id of the client this app runs, which makes the physics treat the app as a client.
+///
+/// Only bodies with a matching `Authority` are simulated. Every other body is remote: its
+/// `Transform` follows the replicated `Position` smoothly instead of snapping to it.
+#[derive(Resource, Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Resource))]
+pub struct LocalClient {
+    pub id: u64,
+    /// Fraction of the remaining distance a remote entity's `Transform` moves every 1/60 of a
+    /// second, scaled to the frame time so it looks the same at any frame rate. `1.0` snaps to
+    /// the replicated position
+    pub remote_interpolation: f32,
+    /// Seconds over which a server correction of a body this client predicts is blended in,
+    /// instead of the body jumping to the corrected position. See `PredictionError`.
+    ///
+    /// `0.0` applies corrections at once.
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub correction_window: f32,
+}
+
+impl LocalClient {
+    pub fn new(id: u64) -> Self {
+        Self {
+            id,
+            remote_interpolation: 0.2,
+            correction_window: 0.15,
+        }
+    }
+}
+
+/// How the physics systems react to NaN or infinite values in `Position` and `Movement`.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+pub enum ValidationPolicy {
+    /// Do not check for non-finite values
+    Off,
+    /// Replace non-finite values with zero
+    Clamp,
+    /// Move the entity back to the last finite position, stop it and remove non-finite forces (default)
+    #[default]
+    ResetToLastGood,
+    /// Panic with a message naming the entity and the offending force
+    Panic,
+}
+
+/// Simulation level of detail, based on the distance to the closest `SimulationAnchor`.
+///
+/// Bodies that skipped ticks catch up on them with a single larger step once they are
+/// integrated again.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serialize", serde(default))]
+pub struct SimulationLod {
+    /// Bodies further than this from every anchor are only integrated every `reduced_interval` ticks
+    pub reduced_distance: f32,
+    pub reduced_interval: u32,
+    /// Bodies further than this from every anchor are not integrated at all
+    pub paused_distance: Option<f32>,
+    /// Maximum number of skipped ticks a body catches up on when it is integrated again
+    pub max_catch_up_ticks: u32,
+    /// Dynamic bodies further than this from every anchor get `SimplifiedPhysics`, so they keep
+    /// moving without colliding, and lose it again once they are closer
+    pub simplified_distance: Option<f32>,
+}
+
+impl Default for SimulationLod {
+    fn default() -> Self {
+        Self {
+            reduced_distance: 64.0,
+            reduced_interval: 4,
+            paused_distance: None,
+            max_catch_up_ticks: 16,
+            simplified_distance: None,
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use bevy_ecs::entity::Entity;
+use bevy_math::prelude::*;
+
+#[cfg(feature = "reflect")]
+use bevy_reflect::prelude::*;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+/// Details about a contact between two colliders, as seen from the first entity of the pair.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+pub struct ContactData {
+    /// Side of the first entity that was touched by the second entity
+    pub side: ContactSide,
+    /// Unit vector pointing from the first entity towards the second, along the shortest way out
+    /// of the overlap. Use `-normal` with `Movement::reflect_velocity()` to bounce the first entity.
+    pub normal: Vec2,
+    /// `UserData` of the first entity, or 0 if it has none
+    pub user_data: u128,
+    /// `UserData` of the second entity, or 0 if it has none
+    pub other_user_data: u128,
+    /// Approximate contact position in world space, the center of the overlap of both colliders
+    pub point: Vec2,
+    /// Velocity of the first entity during the tick, like `Movement::velocity`
+    pub velocity: Vec2,
+    /// Velocity of the second entity during the tick
+    pub other_velocity: Vec2,
+    /// Root of the `RigidGroup` the first entity is part of. The entity itself is the part of the
+    /// compound body that was hit, e.g. the window or the hull of a vehicle.
+    pub group: Option<Entity>,
+    /// Root of the `RigidGroup` the second entity is part of
+    pub other_group: Option<Entity>,
+}
+
+impl ContactData {
+    /// Creates contact data from the minimum translation vector pointing from the first
+    /// entity towards the second.
+    pub fn from_mtv(mtv: Vec2) -> Self {
+        Self {
+            side: ContactSide::from_mtv(mtv),
+            normal: mtv.normalize_or_zero(),
+            user_data: 0,
+            other_user_data: 0,
+            point: Vec2::ZERO,
+            velocity: Vec2::ZERO,
+            other_velocity: Vec2::ZERO,
+            group: None,
+            other_group: None,
+        }
+    }
+
+    /// Returns a copy carrying the `UserData` of both entities.
+    pub fn with_user_data(self, user_data: u128, other_user_data: u128) -> Self {
+        Self {
+            user_data,
+            other_user_data,
+            ..self
+        }
+    }
+
+    /// Returns a copy with the contact point and the velocities of both entities.
+    pub fn with_motion(self, point: Vec2, velocity: Vec2, other_velocity: Vec2) -> Self {
+        Self {
+            point,
+            velocity,
+            other_velocity,
+            ..self
+        }
+    }
+
+    /// Returns a copy with the `RigidGroup` roots of both entities.
+    pub fn with_groups(self, group: Option<Entity>, other_group: Option<Entity>) -> Self {
+        Self {
+            group,
+            other_group,
+            ..self
+        }
+    }
+
+    /// Velocity of the second entity relative to the first, e.g. for doppler effects.
+    pub fn relative_velocity(&self) -> Vec2 {
+        self.other_velocity - self.velocity
+    }
+
+    /// Returns the same contact as seen from the second entity.
+    pub fn flip(self) -> Self {
+        Self {
+            side: self.side.flip(),
+            normal: -self.normal,
+            user_data: self.other_user_data,
+            other_user_data: self.user_data,
+            point: self.point,
+            velocity: self.other_velocity,
+            other_velocity: self.velocity,
+            group: self.other_group,
+            other_group: self.group,
+        }
+    }
+}
+
+/// Side of a collider that was touched.
+///
+/// Uses bevy's coordinate system, so `Top` is towards positive y.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+pub enum ContactSide {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    /// Touched on a rounded corner
+    Corner,
+}
+
+impl ContactSide {
+    /// Returns the side of the first collider that is touched, given the minimum translation
+    /// vector pointing from the first collider towards the second.
+    pub fn from_mtv(mtv: Vec2) -> Self {
+        match (mtv.x == 0.0, mtv.y == 0.0) {
+            (true, false) if mtv.y > 0.0 => Self::Top,
+            (true, false) => Self::Bottom,
+            (false, true) if mtv.x > 0.0 => Self::Right,
+            (false, true) => Self::Left,
+            _ => Self::Corner,
+        }
+    }
+
+    /// Returns the side of the other collider in the same contact.
+    pub fn flip(self) -> Self {
+        match self {
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+            Self::Top => Self::Bottom,
+            Self::Bottom => Self::Top,
+            Self::Corner => Self::Corner,
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use crate::*;
+use bevy_ecs::{entity::EntityHashMap, prelude::*};
+use bevy_math::prelude::*;
+
+/// Every contact of the last physics tick, indexed by entity.
+///
+/// Built from the same contacts as `CollisionMessage`, so it can be walked like a graph, e.g. to
+/// find every box in a row that the player is pushing:
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use pvw_rrect_physics::prelude::*;
+/// fn pushed_row(contacts: &Contacts, player: Entity, direction: bevy_math::Vec2) -> Vec<Entity> {
+///     let mut row = Vec::new();
+///     let mut current = player;
+///     while let Some(next) = contacts
+///         .touching(current)
+///         .find(|contact| !contact.other_static && contact.normal().dot(direction) > 0.5)
+///     {
+///         if row.contains(&next.other) {
+///             break;
+///         }
+///         row.push(next.other);
+///         current = next.other;
+///     }
+///     row
+/// }
+/// ```
+#[derive(Resource, Default, Debug)]
+pub struct Contacts {
+    touching: EntityHashMap<Vec<ContactInfo>>,
+}
+
+impl Contacts {
+    /// Returns the contacts of `entity`, each seen from `entity`.
+    pub fn touching(&self, entity: Entity) -> impl Iterator<Item = ContactInfo> + '_ {
+        self.touching.get(&entity).into_iter().flatten().copied()
+    }
+
+    pub fn is_touching(&self, a: Entity, b: Entity) -> bool {
+        self.touching(a).any(|contact| contact.other == b)
+    }
+
+    /// Returns every entity that had at least one contact.
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.touching.keys().copied()
+    }
+}
+
+/// A contact in `Contacts`, as seen from the entity it was looked up for.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ContactInfo {
+    pub other: Entity,
+    /// Whether the other collider is static, so it cannot be pushed
+    pub other_static: bool,
+    pub data: ContactData,
+}
+
+impl ContactInfo {
+    /// Unit vector pointing from the entity towards `other`.
+    pub fn normal(&self) -> Vec2 {
+        self.data.normal
+    }
+}
+
+pub(crate) fn update_contacts(
+    mut messages: MessageReader<CollisionMessage>,
+    mut contacts: ResMut<Contacts>,
+    colliders: Query<&Collider>,
+) {
+    let is_static = |entity: Entity| {
+        colliders
+            .get(entity)
+            .is_ok_and(|collider| matches!(collider.ctype, ColliderType::Static))
+    };
+
+    contacts.touching.clear();
+
+    for CollisionMessage(a, b, data) in messages.read() {
+        contacts.touching.entry(*a).or_default().push(ContactInfo {
+            other: *b,
+            other_static: is_static(*b),
+            data: *data,
+        });
+        contacts.touching.entry(*b).or_default().push(ContactInfo {
+            other: *a,
+            other_static: is_static(*a),
+            data: data.flip(),
+        });
+    }
+}
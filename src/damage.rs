@@ -0,0 +1,57 @@
+use crate::*;
+use bevy_ecs::prelude::*;
+use bevy_platform::collections::HashMap;
+use bevy_time::prelude::*;
+use std::time::Duration;
+
+/// Sends a `DamageMessage` for every collision with a `ContactDamage` body that is hard enough.
+pub(crate) fn apply_contact_damage(
+    mut collisions: MessageReader<CollisionMessage>,
+    mut damage: MessageWriter<DamageMessage>,
+    sources: Query<&ContactDamage>,
+    time: Res<Time>,
+    // last hit of each (source, target) pair
+    mut last_hits: Local<HashMap<(Entity, Entity), Duration>>,
+) {
+    let now = time.elapsed();
+    let delta = time.delta_secs();
+
+    for CollisionMessage(a, b, data) in collisions.read() {
+        // how fast the two close in along the normal, in tiles per second
+        let impact = if delta > 0.0 {
+            (data.velocity - data.other_velocity)
+                .dot(data.normal)
+                .max(0.0)
+                / delta
+        } else {
+            0.0
+        };
+
+        for (source, target) in [(*a, *b), (*b, *a)] {
+            let Ok(contact_damage) = sources.get(source) else {
+                continue;
+            };
+
+            if impact < contact_damage.min_impact {
+                continue;
+            }
+
+            let cooldown = Duration::from_secs_f32(contact_damage.cooldown.max(0.0));
+            if let Some(last_hit) = last_hits.get(&(source, target))
+                && now < *last_hit + cooldown
+            {
+                continue;
+            }
+
+            last_hits.insert((source, target), now);
+            damage.write(DamageMessage(target, contact_damage.amount));
+        }
+    }
+
+    // forget pairs that can hit again anyway, so despawned entities do not pile up
+    last_hits.retain(|(source, _), last_hit| {
+        sources.get(*source).is_ok_and(|contact_damage| {
+            now < *last_hit + Duration::from_secs_f32(contact_damage.cooldown.max(0.0))
+        })
+    });
+}
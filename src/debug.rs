@@ -0,0 +1,118 @@
+use crate::*;
+use bevy_color::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_gizmos::prelude::*;
+use bevy_math::prelude::*;
+
+#[cfg(feature = "reflect")]
+use bevy_reflect::prelude::*;
+
+/// Controls what is drawn with gizmos, e.g. to reduce noise in busy scenes.
+#[derive(Resource, Clone, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "reflect", reflect(Resource))]
+pub struct PhysicsDebugConfig {
+    pub show_hitboxes: bool,
+    pub static_color: Color,
+    pub dynamic_color: Color,
+    pub sensor_color: Color,
+    /// Draws the axis-aligned bounding box around each rounded collider
+    pub show_aabbs: bool,
+    pub aabb_color: Color,
+    /// Draws each body's displacement of the last tick, multiplied by `velocity_scale`
+    pub show_velocities: bool,
+    pub velocity_color: Color,
+    pub velocity_scale: f32,
+    /// Draws the occupied cells of the `SpatialHashGrid`, only available with the `physics` feature
+    pub show_grid: bool,
+    pub grid_color: Color,
+    /// Only colliders in at least one of these layers are drawn, see `CollisionLayers::memberships`
+    pub layers: LayerMask,
+}
+
+impl Default for PhysicsDebugConfig {
+    fn default() -> Self {
+        Self {
+            show_hitboxes: true,
+            static_color: Color::srgb(0.0, 1.0, 0.0),
+            dynamic_color: Color::srgb(1.0, 1.0, 0.0),
+            sensor_color: Color::srgb(0.0, 1.0, 1.0),
+            show_aabbs: false,
+            aabb_color: Color::srgb(0.5, 0.5, 0.5),
+            show_velocities: false,
+            velocity_color: Color::srgb(1.0, 0.0, 0.0),
+            velocity_scale: 8.0,
+            show_grid: false,
+            grid_color: Color::srgba(1.0, 1.0, 1.0, 0.2),
+            layers: LayerMask::ALL,
+        }
+    }
+}
+
+impl PhysicsDebugConfig {
+    pub fn collider_color(&self, ctype: ColliderType) -> Color {
+        match ctype {
+            ColliderType::Static => self.static_color,
+            ColliderType::Dynamic(_) => self.dynamic_color,
+            ColliderType::Sensor => self.sensor_color,
+        }
+    }
+}
+
+pub(crate) fn draw_hitboxes(
+    mut gizmos: Gizmos,
+    query: Query<(&Collider, &Position, &Movement, Option<&CollisionLayers>)>,
+    tile_size: Res<TileSize>,
+    config: Res<PhysicsDebugConfig>,
+) {
+    let size = tile_size.size();
+    for (collider, pos, movement, layers) in &query {
+        let memberships = layers.map_or(LayerMask::ALL, |layers| layers.memberships);
+        if !memberships.intersects(config.layers) {
+            continue;
+        }
+
+        let center = tile_size.to_world((*pos).into()).0;
+
+        if config.show_hitboxes {
+            gizmos
+                .rounded_rect_2d(
+                    center,
+                    collider.size * size,
+                    config.collider_color(collider.ctype),
+                )
+                .corner_radius(collider.radius * size);
+        }
+
+        if config.show_aabbs {
+            gizmos.rect_2d(center, collider.size * size, config.aabb_color);
+        }
+
+        if config.show_velocities && movement.velocity != Vec2::ZERO {
+            gizmos.arrow_2d(
+                center,
+                center + movement.velocity * config.velocity_scale * size,
+                config.velocity_color,
+            );
+        }
+    }
+}
+
+#[cfg(feature = "physics")]
+pub(crate) fn draw_grid(
+    mut gizmos: Gizmos,
+    grid: Res<SpatialHashGrid>,
+    tile_size: Res<TileSize>,
+    config: Res<PhysicsDebugConfig>,
+) {
+    if !config.show_grid {
+        return;
+    }
+
+    let size = tile_size.size();
+    let cell_size = grid.cell_size() * size;
+    for (cell, _) in grid.cell_occupancy() {
+        let center = (cell.as_vec2() + 0.5) * cell_size;
+        gizmos.rect_2d(center, Vec2::splat(cell_size), config.grid_color);
+    }
+}
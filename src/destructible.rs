@@ -0,0 +1,68 @@
+use crate::*;
+use bevy_ecs::prelude::*;
+use bevy_math::prelude::*;
+
+/// Cuts `region` out of every static `Destructible` collider it overlaps.
+///
+/// The remaining parts of a collider become up to four rectangles: the original entity keeps one
+/// of them and the others are spawned as clones of it with all of its components. Colliders with
+/// nothing left are despawned. The spatial grid is updated immediately, if there is one.
+pub fn damage_region(commands: &mut Commands, region: Rect) {
+    commands.queue(move |world: &mut World| split_destructibles(world, region));
+}
+
+fn split_destructibles(world: &mut World, region: Rect) {
+    let mut query = world.query::<(Entity, &Position, &Collider, &Destructible)>();
+    let damaged: Vec<_> = query
+        .iter(world)
+        .filter(|(_, _, collider, _)| matches!(collider.ctype, ColliderType::Static))
+        .filter_map(|(entity, pos, collider, destructible)| {
+            let rect = Rect::from_center_size(pos.0, collider.size);
+            let hit = rect.intersect(region);
+            (!hit.is_empty()).then_some((entity, *collider, *destructible, rect, hit))
+        })
+        .collect();
+
+    for (entity, collider, destructible, rect, hit) in damaged {
+        let pieces = [
+            // full width strips below and above the hit
+            Rect::from_corners(rect.min, vec2(rect.max.x, hit.min.y)),
+            Rect::from_corners(vec2(rect.min.x, hit.max.y), rect.max),
+            // strips left and right of the hit, between the other two
+            Rect::from_corners(vec2(rect.min.x, hit.min.y), vec2(hit.min.x, hit.max.y)),
+            Rect::from_corners(vec2(hit.max.x, hit.min.y), vec2(rect.max.x, hit.max.y)),
+        ]
+        .into_iter()
+        .filter(|piece| piece.size().min_element() > destructible.min_size.max(0.0));
+
+        let mut kept_original = false;
+        for piece in pieces {
+            let piece_entity = if kept_original {
+                world.entity_mut(entity).clone_and_spawn()
+            } else {
+                kept_original = true;
+                entity
+            };
+
+            let pos = Position(piece.center());
+            let collider = collider.with_size(piece.size());
+            world.entity_mut(piece_entity).insert((pos, collider));
+            let active = world.entity(piece_entity).contains::<ActiveStatic>();
+            // without a physics plugin there is no grid to keep up to date
+            if let Some(mut grid) = world.get_resource_mut::<SpatialHashGrid>() {
+                grid.insert_or_update(
+                    piece_entity,
+                    &pos,
+                    &collider,
+                    Vec2::ZERO,
+                    Vec2::ZERO,
+                    !active,
+                );
+            }
+        }
+
+        if !kept_original {
+            world.despawn(entity);
+        }
+    }
+}
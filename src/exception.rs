@@ -0,0 +1,81 @@
+use bevy_ecs::prelude::*;
+use bevy_platform::collections::{HashMap, HashSet};
+use bevy_time::prelude::*;
+use core::time::Duration;
+
+#[cfg(feature = "reflect")]
+use bevy_reflect::prelude::*;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+/// Entities this entity temporarily does not collide with, and for how much longer.
+///
+/// Usually added with `ignore_collisions_with()`, e.g. to let a projectile leave its shooter or to
+/// drop through a platform.
+#[derive(Component, Default, Clone, Debug)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component))]
+pub struct CollisionExceptions {
+    pub ignored: HashMap<Entity, Duration>,
+}
+
+impl CollisionExceptions {
+    /// Ignores collisions with `other` for at least `duration`, without shortening a longer exception.
+    pub fn ignore(&mut self, other: Entity, duration: Duration) {
+        let remaining = self.ignored.entry(other).or_default();
+        *remaining = (*remaining).max(duration);
+    }
+
+    pub fn is_ignored(&self, other: Entity) -> bool {
+        self.ignored.contains_key(&other)
+    }
+}
+
+/// Adds `ignore_collisions_with()` to `EntityCommands`.
+pub trait IgnoreCollisionsExt {
+    /// Skips collisions between this entity and `other` until `duration` of physics time has passed.
+    fn ignore_collisions_with(&mut self, other: Entity, duration: Duration) -> &mut Self;
+}
+
+impl IgnoreCollisionsExt for EntityCommands<'_> {
+    fn ignore_collisions_with(&mut self, other: Entity, duration: Duration) -> &mut Self {
+        self.entry::<CollisionExceptions>()
+            .or_default()
+            .and_modify(move |mut exceptions| exceptions.ignore(other, duration));
+        self
+    }
+}
+
+pub(crate) fn tick_collision_exceptions(
+    mut query: Query<&mut CollisionExceptions>,
+    time: Res<Time<Fixed>>,
+) {
+    let delta = time.delta();
+
+    for mut exceptions in &mut query {
+        if exceptions.ignored.is_empty() {
+            continue;
+        }
+
+        exceptions.ignored.retain(|_, remaining| {
+            *remaining = remaining.saturating_sub(delta);
+            !remaining.is_zero()
+        });
+    }
+}
+
+/// Returns every ignored pair, with the smaller entity first.
+pub(crate) fn ignored_pairs(
+    query: &Query<(Entity, &CollisionExceptions)>,
+) -> HashSet<(Entity, Entity)> {
+    query
+        .iter()
+        .flat_map(|(entity, exceptions)| {
+            exceptions
+                .ignored
+                .keys()
+                .map(move |&other| (entity.min(other), entity.max(other)))
+        })
+        .collect()
+}
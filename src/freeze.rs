@@ -0,0 +1,42 @@
+use bevy_ecs::prelude::*;
+
+/// Pauses the simulation for a number of fixed ticks, e.g. for hit stop.
+///
+/// Only `PhysicsSystems` are paused, rendering systems in `Update` keep running.
+#[derive(Resource, Default, Clone, Copy, Debug)]
+pub struct PhysicsFreeze {
+    remaining: u32,
+}
+
+impl PhysicsFreeze {
+    pub fn for_ticks(ticks: u32) -> Self {
+        Self { remaining: ticks }
+    }
+
+    /// Freezes for at least `ticks` more ticks, without shortening a longer freeze.
+    pub fn freeze(&mut self, ticks: u32) {
+        self.remaining = self.remaining.max(ticks);
+    }
+
+    pub fn resume(&mut self) {
+        self.remaining = 0;
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.remaining > 0
+    }
+
+    pub fn remaining_ticks(&self) -> u32 {
+        self.remaining
+    }
+}
+
+pub(crate) fn physics_not_frozen(freeze: Res<PhysicsFreeze>) -> bool {
+    !freeze.is_frozen()
+}
+
+pub(crate) fn count_down_freeze(mut freeze: ResMut<PhysicsFreeze>) {
+    if freeze.remaining > 0 {
+        freeze.remaining -= 1;
+    }
+}
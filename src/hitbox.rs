@@ -0,0 +1,46 @@
+use crate::*;
+use bevy_ecs::prelude::*;
+
+/// Moves hitboxes to their owner after the owner moved this tick, before collisions are checked.
+pub(crate) fn sync_hitboxes(
+    mut hitboxes: Query<(&WeldedTo, &mut Position, &mut Movement), With<HitboxKind>>,
+    owners: Query<&Position, Without<HitboxKind>>,
+) {
+    for (weld, mut pos, mut movement) in &mut hitboxes {
+        if let Ok(owner_pos) = owners.get(weld.parent) {
+            let new_pos = owner_pos.0 + weld.offset;
+            movement.velocity = new_pos - pos.0;
+            pos.0 = new_pos;
+        }
+    }
+}
+
+/// Sends a `HitboxMessage` for every collision of a hitbox with a hurtbox of another owner.
+pub(crate) fn send_hitbox_messages(
+    mut collisions: MessageReader<CollisionMessage>,
+    mut hits: MessageWriter<HitboxMessage>,
+    hitboxes: Query<(&HitboxKind, &WeldedTo)>,
+) {
+    for CollisionMessage(a, b, data) in collisions.read() {
+        for (hitbox, hurtbox, contact) in [(*a, *b, *data), (*b, *a, data.flip())] {
+            let (Ok((HitboxKind::Hitbox, attacker)), Ok((HitboxKind::Hurtbox, target))) =
+                (hitboxes.get(hitbox), hitboxes.get(hurtbox))
+            else {
+                continue;
+            };
+
+            // a character never hits itself
+            if attacker.parent == target.parent {
+                continue;
+            }
+
+            hits.write(HitboxMessage {
+                hitbox,
+                hurtbox,
+                attacker: attacker.parent,
+                target: target.parent,
+                contact,
+            });
+        }
+    }
+}
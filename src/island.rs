@@ -0,0 +1,65 @@
+use bevy_ecs::prelude::*;
+use bevy_platform::collections::HashMap;
+
+/// Groups pairs into islands: sets of dynamic bodies connected through pairs.
+///
+/// Given every pair that could touch this tick, no push reaches a body of another island, and
+/// static colliders and sensors never connect two islands since they are not moved by the solver.
+/// Each island can then be solved on its own, with the same result as solving it alone.
+///
+/// `island_key` returns the body that moves when an entity is pushed, or `None` if it is not
+/// dynamic. Entities sharing a key, like members of a rigid group, always end up in the same island.
+pub(crate) fn build_islands(
+    pairs: &[(Entity, Entity)],
+    island_key: impl Fn(Entity) -> Option<Entity>,
+) -> Vec<Vec<(Entity, Entity)>> {
+    let mut parents: HashMap<Entity, Entity> = HashMap::new();
+
+    for &(a, b) in pairs {
+        if let (Some(a), Some(b)) = (island_key(a), island_key(b)) {
+            let root_a = find(&mut parents, a);
+            let root_b = find(&mut parents, b);
+
+            if root_a != root_b {
+                parents.insert(root_a, root_b);
+            }
+        }
+    }
+
+    let mut island_index: HashMap<Entity, usize> = HashMap::new();
+    let mut islands: Vec<Vec<(Entity, Entity)>> = Vec::new();
+
+    for &(a, b) in pairs {
+        let key = island_key(a).or(island_key(b)).unwrap_or(b);
+        let root = find(&mut parents, key);
+
+        let index = *island_index.entry(root).or_insert_with(|| {
+            islands.push(Vec::new());
+            islands.len() - 1
+        });
+
+        islands[index].push((a, b));
+    }
+
+    islands
+}
+
+fn find(parents: &mut HashMap<Entity, Entity>, ent: Entity) -> Entity {
+    let mut root = ent;
+    while let Some(&parent) = parents.get(&root) {
+        root = parent;
+    }
+
+    // path compression
+    let mut current = ent;
+    while let Some(&parent) = parents.get(&current) {
+        if parent == root {
+            break;
+        }
+
+        parents.insert(current, root);
+        current = parent;
+    }
+
+    root
+}
@@ -0,0 +1,187 @@
+use crate::*;
+use bevy_ecs::prelude::*;
+use bevy_math::prelude::*;
+
+/// Number of times all joints are solved per tick. Chains of joints converge over several passes.
+const JOINT_ITERATIONS: usize = 8;
+
+/// Settings for `spawn_rope()`.
+#[derive(Clone, Copy, Debug)]
+pub struct RopeConfig {
+    /// Collider of each segment, should be smaller than the distance between segments
+    pub segment_collider: Collider,
+    /// Length of the rope relative to the distance between its ends, values above 1 add slack
+    pub slack: f32,
+    /// Entity the first segment is tied to, e.g. a grappling hook's owner
+    pub attach_start: Option<Entity>,
+    /// Entity the last segment is tied to, e.g. the anchor of a hanging bridge
+    pub attach_end: Option<Entity>,
+}
+
+impl Default for RopeConfig {
+    fn default() -> Self {
+        Self {
+            segment_collider: Collider::circle(0.2, ColliderType::Dynamic(0.2)),
+            slack: 1.0,
+            attach_start: None,
+            attach_end: None,
+        }
+    }
+}
+
+/// Spawns a chain of `segments` dynamic bodies from `from` to `to`, linked by rope joints.
+///
+/// Returns the segments in order from `from` to `to`. The joints are spawned as separate entities.
+pub fn spawn_rope(
+    commands: &mut Commands,
+    from: Vec2,
+    to: Vec2,
+    segments: usize,
+    config: RopeConfig,
+) -> Vec<Entity> {
+    let segments = segments.max(1);
+    let step = (to - from) / segments as f32;
+    let link_length = step.length() * config.slack;
+
+    let entities: Vec<Entity> = (0..segments)
+        .map(|i| {
+            let pos = from + step * (i as f32 + 0.5);
+            commands
+                .spawn((Position(pos), config.segment_collider))
+                .id()
+        })
+        .collect();
+
+    for pair in entities.windows(2) {
+        commands.spawn(DistanceJoint::rope(pair[0], pair[1], link_length));
+    }
+
+    // the ends sit half a link away from the attached entities
+    if let Some(start) = config.attach_start {
+        commands.spawn(DistanceJoint::rope(start, entities[0], link_length * 0.5));
+    }
+
+    if let Some(end) = config.attach_end {
+        let last = entities[entities.len() - 1];
+        commands.spawn(DistanceJoint::rope(last, end, link_length * 0.5));
+    }
+
+    entities
+}
+
+pub(crate) fn solve_distance_joints(
+    joints: Query<&DistanceJoint>,
+    mut bodies: Query<(&mut Position, Option<&Collider>, Option<&PhysicsGroup>)>,
+    groups: Res<PhysicsGroups>,
+) {
+    // bodies in a disabled group hold their end of the joint in place
+    let inverse_mass = |collider: Option<&Collider>, group: Option<&PhysicsGroup>| match collider
+        .map(|collider| collider.ctype)
+    {
+        Some(ColliderType::Dynamic(mass)) if groups.is_active(group) => 1.0 / mass,
+        _ => 0.0,
+    };
+
+    for _ in 0..JOINT_ITERATIONS {
+        for joint in &joints {
+            let Ok(
+                [
+                    (mut pos_a, collider_a, group_a),
+                    (mut pos_b, collider_b, group_b),
+                ],
+            ) = bodies.get_many_mut([joint.a, joint.b])
+            else {
+                continue;
+            };
+
+            let inv_mass_a = inverse_mass(collider_a, group_a);
+            let inv_mass_b = inverse_mass(collider_b, group_b);
+            let total_inv_mass = inv_mass_a + inv_mass_b;
+            if total_inv_mass == 0.0 {
+                continue;
+            }
+
+            let offset = pos_b.0 - pos_a.0;
+            let distance = offset.length();
+            let target = distance.clamp(joint.min_length, joint.max_length.max(joint.min_length));
+            if distance == target || distance == 0.0 {
+                continue;
+            }
+
+            let correction = offset / distance * (distance - target) / total_inv_mass;
+            pos_a.0 += correction * inv_mass_a;
+            pos_b.0 -= correction * inv_mass_b;
+        }
+    }
+}
+
+/// Pulls every body with a `TetherConstraint` back within its length of the anchor.
+pub(crate) fn solve_tethers(
+    mut tethered: Query<(
+        Entity,
+        &TetherConstraint,
+        &mut Movement,
+        Option<&PhysicsGroup>,
+    )>,
+    mut positions: Query<&mut Position>,
+    config: Res<PhysicsConfig>,
+    groups: Res<PhysicsGroups>,
+) {
+    for (entity, tether, mut movement, group) in &mut tethered {
+        if !groups.is_active(group) {
+            continue;
+        }
+
+        let anchor = match tether.anchor {
+            TetherAnchor::Point(point) => point,
+            TetherAnchor::Entity(anchor) => match positions.get(anchor) {
+                Ok(pos) => pos.0,
+                Err(_) => continue,
+            },
+        };
+
+        let Ok(mut pos) = positions.get_mut(entity) else {
+            continue;
+        };
+
+        let offset = config.topology.delta(anchor, pos.0);
+        let distance = offset.length();
+        if distance <= tether.max_length || distance == 0.0 {
+            continue;
+        }
+
+        let outward = offset / distance;
+        pos.0 -= outward * (distance - tether.max_length);
+
+        // the edge of the radius acts like a wall, so forces do not keep pulling outwards
+        movement.reflect_velocity(-outward, 0.0);
+    }
+}
+
+/// Despawns every joint attached to an entity that lost its `Position`, usually because it was
+/// despawned, so no joint keeps pointing at a dead entity.
+pub(crate) fn break_joints_of_removed_body(
+    remove: On<Remove, Position>,
+    joints: Query<(Entity, &DistanceJoint)>,
+    mut broken: MessageWriter<JointBroken>,
+    mut commands: Commands,
+) {
+    for (joint, distance_joint) in &joints {
+        if distance_joint.a != remove.entity && distance_joint.b != remove.entity {
+            continue;
+        }
+
+        // the joint may already be gone when both of its ends are despawned at once
+        let Ok(mut joint_commands) = commands.get_entity(joint) else {
+            continue;
+        };
+        joint_commands.remove::<DistanceJoint>().try_despawn();
+
+        broken.write(JointBroken {
+            joint,
+            a: distance_joint.a,
+            b: distance_joint.b,
+            removed: remove.entity,
+        });
+    }
+}
@@ -0,0 +1,209 @@
+use crate::*;
+use bevy_ecs::{prelude::*, query::QueryFilter};
+use bevy_math::prelude::*;
+
+/// Outcome of `move_and_slide()`.
+#[derive(Clone, Debug, Default)]
+pub struct SlideResult {
+    /// Position after moving and sliding along static colliders
+    pub position: Vec2,
+    /// Part of the desired movement that was blocked
+    pub remaining: Vec2,
+    /// Static colliders that were hit, with the contact as seen from the moving entity
+    pub contacts: Vec<(Entity, ContactData)>,
+}
+
+/// Moves a collider by `delta` without going through static colliders, sliding along them instead.
+///
+/// Meant for code that sets positions directly, like cutscenes or network corrections.
+/// Nothing is changed in the world, write `SlideResult::position` to the entity's `Position`.
+/// `entity` is only used to skip the moving entity's own collider.
+///
+/// At most `PhysicsConfig::max_slide_steps` steps are taken, the part of `delta` beyond them is
+/// returned in `SlideResult::remaining`. A non-finite `delta`, or one so long that its length
+/// overflows, does not move the collider at all.
+pub fn move_and_slide<F: QueryFilter>(
+    entity: Entity,
+    pos: Vec2,
+    collider: &Collider,
+    delta: Vec2,
+    grid: &SpatialHashGrid,
+    colliders: &Query<(&Position, &Collider), F>,
+    config: &PhysicsConfig,
+) -> SlideResult {
+    let length = delta.length();
+    if !length.is_finite() || !pos.is_finite() {
+        return SlideResult {
+            position: pos,
+            remaining: delta,
+            contacts: Vec::new(),
+        };
+    }
+
+    // small steps so thin colliders are not skipped
+    let step_length = (collider.size.min_element() * 0.5).max(0.01);
+    let steps = (length / step_length).ceil().max(1.0);
+    let mut step = delta / steps;
+    let max_steps = steps.min(config.max_slide_steps as f32) as u32;
+
+    let mut position = pos;
+    let mut contacts: Vec<(Entity, ContactData)> = Vec::new();
+
+    for _ in 0..max_steps {
+        if step == Vec2::ZERO {
+            break;
+        }
+
+        position += step;
+
+        let half_size = collider.size * 0.5;
+        for other in grid.entities_in_rect(position - half_size, position + half_size) {
+            if other == entity {
+                continue;
+            }
+
+            let Ok((other_pos, other_collider)) = colliders.get(other) else {
+                continue;
+            };
+
+            if !matches!(other_collider.ctype, ColliderType::Static) {
+                continue;
+            }
+
+            let Some(mtv) = collider.penetration(position, other_collider, other_pos.0) else {
+                continue;
+            };
+
+            position -= mtv;
+
+            // slide by dropping the part of the movement that goes into the collider
+            let normal = mtv.normalize_or_zero();
+            let into = step.dot(normal);
+            if into > 0.0 {
+                step -= normal * into;
+            }
+
+            let contact = ContactData::from_mtv(mtv);
+            match contacts.iter_mut().find(|(hit, _)| *hit == other) {
+                Some((_, existing)) => *existing = contact,
+                None => contacts.push((other, contact)),
+            }
+        }
+    }
+
+    SlideResult {
+        position,
+        remaining: delta - (position - pos),
+        contacts,
+    }
+}
+
+/// Penetration into static colliders accepted by `validate_client_position()`, since resting
+/// contacts keep up to `PhysicsConfig::slop` of it, with some room for float error
+fn validation_tolerance(config: &PhysicsConfig) -> f32 {
+    (config.slop * 2.0).max(1e-4)
+}
+
+/// Outcome of `validate_client_position()`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ValidationResult {
+    Valid,
+    /// The entity has no `Position` and `Collider` in the query
+    UnknownEntity,
+    /// The claimed position is further away than the entity can move at `max_speed`
+    TooFast {
+        distance: f32,
+        max_distance: f32,
+    },
+    /// The claimed position overlaps a static collider
+    InsideStatic {
+        blocker: Entity,
+        penetration: Vec2,
+    },
+    /// The claimed position can only be reached by going through static colliders.
+    /// `reachable` is the closest position that can be reached by sliding along them.
+    PassedThroughStatic {
+        reachable: Vec2,
+    },
+}
+
+impl ValidationResult {
+    pub fn is_valid(&self) -> bool {
+        matches!(self, Self::Valid)
+    }
+}
+
+/// Checks a position reported by a client against the static world, e.g. as a server-side
+/// anti-cheat for bodies with `Authority::Client`.
+///
+/// The move from the entity's current `Position` to `claimed_pos` must be possible within `dt`
+/// seconds at `max_speed` tiles per second, must not end inside a static collider and must not
+/// pass through one. Nothing is changed in the world, the server decides what to do with invalid
+/// positions, e.g. keep the current one or use `PassedThroughStatic::reachable`.
+///
+/// Overlaps and distances up to twice `PhysicsConfig::slop` are accepted, so resting contacts
+/// are valid.
+pub fn validate_client_position<F: QueryFilter>(
+    entity: Entity,
+    claimed_pos: Vec2,
+    max_speed: f32,
+    dt: f32,
+    grid: &SpatialHashGrid,
+    colliders: &Query<(&Position, &Collider), F>,
+    config: &PhysicsConfig,
+) -> ValidationResult {
+    let Ok((pos, collider)) = colliders.get(entity) else {
+        return ValidationResult::UnknownEntity;
+    };
+
+    let tolerance = validation_tolerance(config);
+    let distance = pos.0.distance(claimed_pos);
+    let max_distance = max_speed * dt + tolerance;
+    if !claimed_pos.is_finite() || distance > max_distance {
+        return ValidationResult::TooFast {
+            distance,
+            max_distance,
+        };
+    }
+
+    let half_size = collider.size * 0.5;
+    for other in grid.entities_in_rect(claimed_pos - half_size, claimed_pos + half_size) {
+        if other == entity {
+            continue;
+        }
+
+        let Ok((other_pos, other_collider)) = colliders.get(other) else {
+            continue;
+        };
+
+        if !matches!(other_collider.ctype, ColliderType::Static) {
+            continue;
+        }
+
+        if let Some(penetration) = collider.penetration(claimed_pos, other_collider, other_pos.0)
+            && penetration.length() > tolerance
+        {
+            return ValidationResult::InsideStatic {
+                blocker: other,
+                penetration,
+            };
+        }
+    }
+
+    let slide = move_and_slide(
+        entity,
+        pos.0,
+        collider,
+        claimed_pos - pos.0,
+        grid,
+        colliders,
+        config,
+    );
+    if slide.position.distance(claimed_pos) > tolerance {
+        return ValidationResult::PassedThroughStatic {
+            reachable: slide.position,
+        };
+    }
+
+    ValidationResult::Valid
+}
@@ -0,0 +1,107 @@
+use crate::*;
+use bevy_ecs::prelude::*;
+use bevy_math::prelude::*;
+use bevy_time::prelude::*;
+
+/// Speed in tiles per second below which the knockback is removed
+const KNOCKBACK_EPSILON: f32 = 1e-3;
+
+/// Adds `apply_knockback()` to `EntityCommands`.
+pub trait KnockbackExt {
+    /// Knocks a dynamic body away from `source_pos`, e.g. when it is hit by an attack.
+    ///
+    /// The body is launched at `strength` divided by its mass in tiles per second, at most
+    /// `Movement::MAX_VELOCITY`. The speed is stored in the inactive force
+    /// `Movement::KNOCKBACK_FORCE`, replacing the knockback of an earlier hit. It wears off at
+    /// `Movement::KNOCKBACK_DECAY` and with the body's damping like any other inactive force. The
+    /// part of it moving into a static collider is dropped on contact, so a body knocked into a
+    /// wall slides along it instead of sticking. Static colliders and sensors are not knocked
+    /// back, a body without `Movement` gets one.
+    fn apply_knockback(&mut self, source_pos: Vec2, strength: f32) -> &mut Self;
+}
+
+impl KnockbackExt for EntityCommands<'_> {
+    fn apply_knockback(&mut self, source_pos: Vec2, strength: f32) -> &mut Self {
+        self.queue(move |mut entity: EntityWorldMut| {
+            let (Some(pos), Some(collider)) = (entity.get::<Position>(), entity.get::<Collider>())
+            else {
+                return;
+            };
+
+            let ColliderType::Dynamic(mass) = collider.ctype else {
+                return;
+            };
+
+            let speed = (strength / mass.max(f32::EPSILON)).min(Movement::MAX_VELOCITY);
+            let knockback = (pos.0 - source_pos).normalize_or_zero() * speed;
+
+            let mut movement = entity.entry::<Movement>().or_default().into_mut();
+            movement.apply_force(PartialForce {
+                id: Movement::KNOCKBACK_FORCE.to_owned(),
+                force: Some(knockback),
+                active: Some(false),
+                ..Default::default()
+            });
+        });
+        self
+    }
+}
+
+/// Wears off `Movement::KNOCKBACK_FORCE` at `Movement::KNOCKBACK_DECAY`, and removes it once it
+/// is too small to move the body.
+pub(crate) fn decay_knockback(
+    mut movements: Query<(Entity, &mut Movement)>,
+    time: Res<Time<Fixed>>,
+    lod_steps: Res<lod::LodSteps>,
+) {
+    for (entity, mut movement) in &mut movements {
+        let Some(force) = movement.forces.get(Movement::KNOCKBACK_FORCE) else {
+            continue;
+        };
+
+        let steps = lod_steps.steps(entity);
+        if steps == 0 || movement.is_group_paused(force.group) {
+            continue;
+        }
+
+        let dt = time.delta_secs() * steps as f32;
+        let force = force.force * (-Movement::KNOCKBACK_DECAY * dt).exp();
+        if force.length_squared() < KNOCKBACK_EPSILON * KNOCKBACK_EPSILON {
+            movement.forces.remove(Movement::KNOCKBACK_FORCE);
+        } else {
+            movement.apply_force(PartialForce {
+                id: Movement::KNOCKBACK_FORCE.to_owned(),
+                force: Some(force),
+                ..Default::default()
+            });
+        }
+    }
+}
+
+/// Drops the part of the knockback that moves a body into a static collider it touched.
+pub(crate) fn stop_knockback_at_walls(
+    static_contacts: Res<solver::StaticContacts>,
+    mut movements: Query<&mut Movement>,
+) {
+    for (&entity, normals) in &static_contacts.0 {
+        let Ok(mut movement) = movements.get_mut(entity) else {
+            continue;
+        };
+
+        for &normal in normals {
+            // the normal points from the body into the static collider
+            let Some(force) = movement.forces.get(Movement::KNOCKBACK_FORCE) else {
+                break;
+            };
+            let into_wall = force.force.dot(normal);
+            if into_wall > 0.0 {
+                let force = force.force - normal * into_wall;
+                movement.apply_force(PartialForce {
+                    id: Movement::KNOCKBACK_FORCE.to_owned(),
+                    force: Some(force),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use bevy_ecs::prelude::*;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not};
+
+#[cfg(feature = "reflect")]
+use bevy_reflect::prelude::*;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+/// Set of up to 32 collision layers, numbered `0..32`.
+///
+/// Serialized as the list of contained layers, e.g. `[0, 3]`, which is easier to author in scene
+/// files than the raw bits. A plain number is accepted too when deserializing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "reflect", reflect(Default, PartialEq, Hash))]
+#[cfg_attr(
+    all(feature = "reflect", feature = "serialize"),
+    reflect(Serialize, Deserialize)
+)]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(
+    feature = "serialize",
+    serde(from = "LayerMaskRepr", into = "LayerMaskRepr")
+)]
+pub struct LayerMask(pub u32);
+
+impl LayerMask {
+    pub const NONE: Self = Self(0);
+    pub const ALL: Self = Self(u32::MAX);
+
+    /// Mask containing only `layer`, which must be below 32.
+    pub const fn layer(layer: u32) -> Self {
+        assert!(layer < 32, "collision layers are numbered 0..32");
+        Self(1 << layer)
+    }
+
+    /// Mask containing every layer in `layers`.
+    pub fn from_layers(layers: impl IntoIterator<Item = u32>) -> Self {
+        layers
+            .into_iter()
+            .fold(Self::NONE, |mask, layer| mask | Self::layer(layer))
+    }
+
+    /// Returns the layers in this mask in ascending order.
+    pub fn layers(self) -> impl Iterator<Item = u32> {
+        (0..32).filter(move |layer| self.0 & (1 << layer) != 0)
+    }
+
+    /// Returns true if every layer of `other` is in this mask.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns true if the masks share at least one layer.
+    pub const fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    pub fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+
+    pub fn remove(&mut self, other: Self) {
+        self.0 &= !other.0;
+    }
+}
+
+impl BitOr for LayerMask {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for LayerMask {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitAnd for LayerMask {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl BitAndAssign for LayerMask {
+    fn bitand_assign(&mut self, rhs: Self) {
+        self.0 &= rhs.0;
+    }
+}
+
+impl Not for LayerMask {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self(!self.0)
+    }
+}
+
+#[cfg(feature = "serialize")]
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+enum LayerMaskRepr {
+    Layers(Vec<u32>),
+    Bits(u32),
+}
+
+#[cfg(feature = "serialize")]
+impl From<LayerMaskRepr> for LayerMask {
+    fn from(repr: LayerMaskRepr) -> Self {
+        match repr {
+            // out of range layers are ignored instead of failing the whole scene
+            LayerMaskRepr::Layers(layers) => {
+                Self::from_layers(layers.into_iter().filter(|layer| *layer < 32))
+            },
+            LayerMaskRepr::Bits(bits) => Self(bits),
+        }
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl From<LayerMask> for LayerMaskRepr {
+    fn from(mask: LayerMask) -> Self {
+        Self::Layers(mask.layers().collect())
+    }
+}
+
+/// Collision layers of an entity. Entities without it are in every layer and collide with every layer.
+///
+/// Two colliders only interact if each one is in a layer the other one collides with.
+///
+/// Layers can be changed at any time, e.g. to flip a player between a detectable and a ghost layer.
+/// Every physics tick uses the current layers, nothing is cached per layer. Sensors report the
+/// overlaps that start or end as a result with `SensorOverlapStarted` and `SensorOverlapEnded`.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "reflect", derive(Reflect))]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "reflect", reflect(Component, Default))]
+pub struct CollisionLayers {
+    /// Layers the entity is in
+    pub memberships: LayerMask,
+    /// Layers the entity collides with
+    pub filters: LayerMask,
+}
+
+impl Default for CollisionLayers {
+    fn default() -> Self {
+        Self {
+            memberships: LayerMask::ALL,
+            filters: LayerMask::ALL,
+        }
+    }
+}
+
+impl CollisionLayers {
+    pub fn new(memberships: LayerMask, filters: LayerMask) -> Self {
+        Self {
+            memberships,
+            filters,
+        }
+    }
+
+    /// Returns true if the two entities should collide.
+    pub fn interacts_with(&self, other: &Self) -> bool {
+        self.memberships.intersects(other.filters) && other.memberships.intersects(self.filters)
+    }
+}
@@ -1,215 +1,1465 @@
 //! An axis-aligned round rectangle implementation for the bevy game engine
 
+#[cfg(not(any(feature = "physics", feature = "render")))]
+compile_error!(
+    "pvw_rrect_physics has no plugin without the `singleplayer`, `client`, `server` or `physics` \
+     feature"
+);
+
+#[cfg(feature = "physics")]
+mod activation;
+#[cfg(feature = "physics")]
+mod arena;
+#[cfg(feature = "assets")]
+mod asset;
+#[cfg(feature = "physics")]
+mod avoidance;
+#[cfg(feature = "camera-follow")]
+mod camera;
 mod components;
+mod config;
+mod contact;
+#[cfg(feature = "physics")]
+mod contact_graph;
+#[cfg(feature = "physics")]
+mod damage;
+#[cfg(feature = "gizmos")]
+mod debug;
+#[cfg(feature = "physics")]
+mod destructible;
+#[cfg(feature = "physics")]
+mod exception;
+#[cfg(feature = "physics")]
+mod freeze;
+#[cfg(feature = "physics")]
+mod hitbox;
+#[cfg(feature = "physics")]
+mod island;
+#[cfg(feature = "physics")]
+mod joint;
+#[cfg(feature = "physics")]
+mod kinematic;
+#[cfg(feature = "physics")]
+mod knockback;
+mod layer;
+#[cfg(feature = "physics")]
+mod lod;
+#[cfg(all(feature = "physics", feature = "render"))]
+mod prediction;
+#[cfg(feature = "scripting")]
+pub mod scripting;
+#[cfg(feature = "physics")]
+mod sensor;
+#[cfg(feature = "physics")]
+mod solver;
 #[cfg(feature = "physics")]
 mod spatial_grid;
+#[cfg(feature = "metrics")]
+pub mod telemetry;
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+#[cfg(feature = "render")]
+pub mod units;
+#[cfg(feature = "physics")]
+mod vision;
+#[cfg(all(feature = "physics", feature = "serialize"))]
+mod world_state;
 
-pub use components::{Collider, ColliderType, Force, Movement, PartialForce, Position};
-pub use spatial_grid::SpatialHashGrid;
+#[cfg(feature = "physics")]
+pub use activation::{PhysicsGroupCommandsExt, PhysicsGroups};
+#[cfg(feature = "assets")]
+pub use asset::{
+    PhysicsAssetError, PhysicsConfigAsset, PhysicsConfigHandle, PhysicsMaterial, PhysicsPrefab,
+    PhysicsPrefabAsset, PhysicsPrefabPlugin,
+};
+#[cfg(feature = "camera-follow")]
+pub use camera::PhysicsCameraFollow;
+pub use components::{
+    ActiveStatic, Authority, Avoidance, Collider, ColliderError, ColliderTween, ColliderType,
+    ConstantForce, ContactCount, ContactDamage, DampingMode, Density, Destructible, DistanceJoint,
+    DynamicBody, FleePoint, Force, ForceGroup, Friction, HitboxKind, LinearDisplacement, Movement,
+    NoInterpolation, PartialForce, PhysicsGroup, Platform, Position, PredictionError, PushPriority,
+    RenderOffset, RigidGroup, SeekTarget, SensorBody, SensorGroup, Silent, SimplifiedPhysics,
+    SimulationAnchor, SpeculativeMargin, SpringFollower, StaticBody, StepUp, SyncMode,
+    TetherAnchor, TetherConstraint, TrackContacts, UserData, WallSlide, WeldedTo, ZIndexMode,
+};
+pub use config::{
+    ArenaBounds, BoundaryResponse, Interpolation, LocalClient, PhysicsConfig, SimulationLod,
+    ValidationPolicy, WorldTopology,
+};
+pub use contact::{ContactData, ContactSide};
+#[cfg(feature = "physics")]
+pub use contact_graph::{ContactInfo, Contacts};
+#[cfg(feature = "gizmos")]
+pub use debug::PhysicsDebugConfig;
+#[cfg(feature = "physics")]
+pub use destructible::damage_region;
+#[cfg(feature = "physics")]
+pub use exception::{CollisionExceptions, IgnoreCollisionsExt};
+#[cfg(feature = "physics")]
+pub use freeze::PhysicsFreeze;
+#[cfg(feature = "physics")]
+pub use joint::{RopeConfig, spawn_rope};
+#[cfg(feature = "physics")]
+pub use kinematic::{SlideResult, ValidationResult, move_and_slide, validate_client_position};
+#[cfg(feature = "physics")]
+pub use knockback::KnockbackExt;
+pub use layer::{CollisionLayers, LayerMask};
+#[cfg(feature = "physics")]
+pub use sensor::{SensorFilter, SensorMessage, SensorMessagePlugin};
+#[cfg(feature = "physics")]
+pub use spatial_grid::{GridMemoryUsage, SpatialHashGrid};
+#[cfg(feature = "render")]
+pub use units::TileSize;
+#[cfg(feature = "physics")]
+pub use vision::vision_query;
+#[cfg(all(feature = "physics", feature = "serialize"))]
+pub use world_state::{BodyState, PhysicsWorldState};
 
+/// Commonly used plugins, components, messages and resources.
+///
+/// `use pvw_rrect_physics::prelude::*;` to import them all.
+pub mod prelude {
+    #[cfg(feature = "camera-follow")]
+    pub use crate::PhysicsCameraFollow;
+    #[cfg(feature = "gizmos")]
+    pub use crate::PhysicsDebugConfig;
+    #[cfg(feature = "singleplayer")]
+    pub use crate::PvwRRectPhysicsPlugin;
+    #[cfg(feature = "server")]
+    pub use crate::PvwRRectPhysicsPluginServer;
+    pub use crate::{
+        ActiveStatic, ArenaBounds, Authority, Avoidance, BoundaryResponse, Collider, ColliderError,
+        ColliderTween, ColliderType, CollisionLayers, ConstantForce, ContactCount, ContactDamage,
+        ContactData, ContactSide, DampingMode, Density, Destructible, DistanceJoint, DynamicBody,
+        FleePoint, Force, ForceGroup, Friction, HitboxKind, Interpolation, LayerMask,
+        LinearDisplacement, LocalClient, Movement, NoInterpolation, PartialForce, PhysicsConfig,
+        PhysicsGroup, Platform, Position, PredictionError, PushPriority, RenderOffset, RigidGroup,
+        SeekTarget, SensorBody, SensorGroup, Silent, SimplifiedPhysics, SimulationAnchor,
+        SimulationLod, SpeculativeMargin, SpringFollower, StaticBody, StepUp, SyncMode,
+        TetherAnchor, TetherConstraint, TrackContacts, UserData, ValidationPolicy, WallSlide,
+        WeldedTo, WorldTopology, ZIndexMode,
+    };
+    #[cfg(all(feature = "physics", feature = "serialize"))]
+    pub use crate::{BodyState, PhysicsWorldState};
+    #[cfg(feature = "physics")]
+    pub use crate::{
+        CellChanged, ColliderTypeChanged, CollisionExceptions, CollisionMessage, ContactInfo,
+        Contacts, ContainedWithin, DamageMessage, ForceClamped, FrameCollisionMessage,
+        GroupOverlapEnded, GroupOverlapStarted, HitboxMessage, IgnoreCollisionsExt, JointBroken,
+        KnockbackExt, MovementBlocked, PairBudgetExceeded, PhysicsAnomaly, PhysicsAnomalyKind,
+        PhysicsFreeze, PhysicsGroupCommandsExt, PhysicsGroups, PhysicsPluginBuilder,
+        PhysicsSystems, PotentialContact, PvwRRectPhysicsPluginHeadless, RopeConfig, SensorFilter,
+        SensorMessage, SensorMessagePlugin, SensorOverlapEnded, SensorOverlapStarted, SlideResult,
+        SpatialHashGrid, ValidationResult, damage_region, move_and_slide, spawn_rope,
+        validate_client_position, vision_query,
+    };
+    #[cfg(feature = "assets")]
+    pub use crate::{
+        PhysicsConfigAsset, PhysicsConfigHandle, PhysicsPrefab, PhysicsPrefabAsset,
+        PhysicsPrefabPlugin,
+    };
+    #[cfg(feature = "render")]
+    pub use crate::{
+        PvwRRectPhysicsPluginClient,
+        units::{PhysicsPos, TilePos, TileSize, WorldPos},
+    };
+}
+
+#[cfg(any(feature = "physics", feature = "render"))]
 use bevy_app::prelude::*;
-#[cfg(feature = "gizmos")]
-use bevy_color::prelude::*;
+#[cfg(any(feature = "physics", feature = "render"))]
+use bevy_ecs::entity::EntityHashMap;
+#[cfg(any(feature = "physics", feature = "render"))]
 use bevy_ecs::prelude::*;
-#[cfg(feature = "gizmos")]
-use bevy_gizmos::prelude::*;
-#[cfg(feature = "physics")]
+#[cfg(any(feature = "physics", feature = "render"))]
 use bevy_math::prelude::*;
 #[cfg(feature = "physics")]
-use bevy_platform::collections::{HashMap, HashSet};
-#[cfg(feature = "physics")]
+use bevy_platform::{
+    collections::{HashMap, HashSet},
+    hash::FixedHasher,
+};
+#[cfg(any(feature = "physics", feature = "render"))]
 use bevy_time::prelude::*;
 #[cfg(feature = "render")]
 use bevy_transform::components::Transform;
+#[cfg(feature = "render")]
+use units::{PhysicsPos, WorldPos};
 
-/// Physics plugin for singleplayer games
-#[cfg(feature = "singleplayer")]
-pub struct PvwRRectPhysicsPlugin {
-    pub spatial_grid_size: f32,
+/// Settings shared by the physics plugins.
+///
+/// ```
+/// # use bevy_math::prelude::*;
+/// # use pvw_rrect_physics::prelude::*;
+/// let plugin = PhysicsPluginBuilder::new()
+///     .cell_size(4.0)
+///     .tick_rate(60.0)
+///     .gravity(vec2(0.0, -20.0))
+///     .interpolation(Interpolation::Lerp(0.2))
+///     .build();
+/// ```
+///
+/// The `PhysicsConfig` is inserted as a resource when the plugin is added, and can still be
+/// changed at runtime.
+#[cfg(feature = "physics")]
+#[derive(Clone, Debug)]
+pub struct PhysicsPluginBuilder {
+    cell_size: f32,
+    tick_rate_hz: Option<f64>,
+    config: PhysicsConfig,
 }
 
-#[cfg(feature = "singleplayer")]
-impl Default for PvwRRectPhysicsPlugin {
+#[cfg(feature = "physics")]
+impl Default for PhysicsPluginBuilder {
     fn default() -> Self {
         Self {
-            spatial_grid_size: SpatialHashGrid::DEFAULT_CELL_SIZE,
+            cell_size: SpatialHashGrid::DEFAULT_CELL_SIZE,
+            tick_rate_hz: None,
+            config: PhysicsConfig::default(),
         }
     }
 }
 
+#[cfg(feature = "physics")]
+impl PhysicsPluginBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Size of a `SpatialHashGrid` cell in tiles. Smaller cells mean fewer pairs to check for
+    /// small colliders, but more cells per large collider.
+    pub fn cell_size(mut self, cell_size: f32) -> Self {
+        self.cell_size = cell_size;
+        self
+    }
+
+    /// Rate of the `FixedUpdate` schedule the simulation runs in, in ticks per second.
+    ///
+    /// `Time<Fixed>` is left untouched if not set (64 Hz unless configured elsewhere).
+    /// Forces and `Movement::MAX_VELOCITY` are in tiles per second, so they do not need to be
+    /// retuned when the tick rate changes.
+    pub fn tick_rate(mut self, hz: f64) -> Self {
+        self.tick_rate_hz = Some(hz);
+        self
+    }
+
+    /// See `PhysicsConfig::gravity`.
+    pub fn gravity(mut self, gravity: Vec2) -> Self {
+        self.config.gravity = gravity;
+        self
+    }
+
+    /// See `PhysicsConfig::interpolation`.
+    pub fn interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.config.interpolation = interpolation;
+        self
+    }
+
+    /// See `PhysicsConfig::pixel_snap`.
+    pub fn pixel_snap(mut self, pixel_snap: bool) -> Self {
+        self.config.pixel_snap = pixel_snap;
+        self
+    }
+
+    /// Replaces the whole `PhysicsConfig`, including gravity and interpolation set before.
+    pub fn config(mut self, config: PhysicsConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Creates the singleplayer plugin.
+    #[cfg(feature = "singleplayer")]
+    pub fn build(self) -> PvwRRectPhysicsPlugin {
+        PvwRRectPhysicsPlugin(self)
+    }
+
+    /// Creates the multiplayer client plugin, the settings are used for the bodies the client
+    /// simulates itself.
+    #[cfg(feature = "render")]
+    pub fn build_client(self) -> PvwRRectPhysicsPluginClient {
+        PvwRRectPhysicsPluginClient(self)
+    }
+
+    /// Creates the multiplayer server plugin.
+    #[cfg(feature = "server")]
+    pub fn build_server(self) -> PvwRRectPhysicsPluginServer {
+        PvwRRectPhysicsPluginServer(self)
+    }
+
+    /// Creates the plugin that only runs the simulation.
+    pub fn build_headless(self) -> PvwRRectPhysicsPluginHeadless {
+        PvwRRectPhysicsPluginHeadless(self)
+    }
+}
+
+/// Physics plugin for singleplayer games
+///
+/// Use `PhysicsPluginBuilder` to change the settings.
+#[cfg(feature = "singleplayer")]
+#[derive(Default)]
+pub struct PvwRRectPhysicsPlugin(PhysicsPluginBuilder);
+
 #[cfg(feature = "singleplayer")]
 impl Plugin for PvwRRectPhysicsPlugin {
     fn build(&self, app: &mut App) {
-        #[cfg(feature = "reflect")]
-        app.add_plugins(type_registry);
-        app.init_resource::<TileSize>();
-        app.insert_resource(SpatialHashGrid {
-            cell_size: self.spatial_grid_size,
-            ..Default::default()
-        });
-        app.add_message::<CollisionMessage>();
-        app.configure_sets(FixedUpdate, PhysicsSystems);
-        app.add_systems(
-            FixedUpdate,
-            (
-                update_velocity_and_predict,
-                update_spatial_hash_grid,
-                check_collisions_and_resolve,
-            )
-                .chain()
-                .in_set(PhysicsSystems),
-        );
-        app.add_systems(
-            Update,
-            (
-                update_translation,
-                #[cfg(feature = "gizmos")]
-                draw_hitboxes,
-            ),
-        );
-        app.add_systems(PostUpdate, translation_just_added);
+        assert_single_plugin(app, "PvwRRectPhysicsPlugin", true, true);
+        app.add_plugins(PvwRRectPhysicsPluginHeadless(self.0.clone()));
+        build_render(app);
     }
 }
 
 /// Physics plugin for multiplayer games on client side
-#[cfg(feature = "client")]
-pub struct PvwRRectPhysicsPluginClient;
+///
+/// Syncs `Transform` with `Position`, the simulation is expected to run on the server. With the
+/// `physics` feature and a `LocalClient` resource, the client also simulates the bodies it has
+/// `Authority` over, e.g. its own player.
+///
+/// A host that runs the server and plays in the same app adds it together with
+/// `PvwRRectPhysicsPluginServer`, in any order. The server plugin then simulates every body and
+/// this plugin only syncs `Transform`, so the host has no `LocalClient`.
+///
+/// ```
+/// # use bevy_app::prelude::*;
+/// # use pvw_rrect_physics::prelude::*;
+/// # #[cfg(feature = "server")]
+/// App::new().add_plugins((
+///     PhysicsPluginBuilder::new().cell_size(4.0).build_server(),
+///     PvwRRectPhysicsPluginClient::default(),
+/// ));
+/// ```
+///
+/// Use `PhysicsPluginBuilder` to change the settings.
+#[cfg(feature = "render")]
+#[derive(Default)]
+pub struct PvwRRectPhysicsPluginClient(#[cfg(feature = "physics")] PhysicsPluginBuilder);
 
-#[cfg(feature = "client")]
+#[cfg(feature = "render")]
 impl Plugin for PvwRRectPhysicsPluginClient {
     fn build(&self, app: &mut App) {
+        assert_single_plugin(app, "PvwRRectPhysicsPluginClient", true, false);
         #[cfg(feature = "reflect")]
-        app.add_plugins(type_registry);
-        app.init_resource::<TileSize>();
-        app.add_systems(
-            Update,
-            (
-                update_translation,
-                #[cfg(feature = "gizmos")]
-                draw_hitboxes,
-            ),
-        );
-        app.add_systems(PostUpdate, translation_just_added);
+        type_registry(app);
+        // a host simulates everything with the server plugin added before
+        #[cfg(feature = "physics")]
+        if !app.is_plugin_added::<PvwRRectPhysicsPluginHeadless>() {
+            build_physics(app, &self.0);
+            // a server plugin added after this one simulates everything as well
+            app.configure_sets(
+                FixedUpdate,
+                PhysicsSystems
+                    .run_if(resource_exists::<LocalClient>.or(resource_exists::<ServerSimulation>)),
+            );
+            app.init_resource::<prediction::PredictedPositions>();
+            app.add_systems(
+                FixedUpdate,
+                (
+                    prediction::smooth_corrections
+                        .after(position_from_transform)
+                        .before(PhysicsSystems),
+                    prediction::record_predictions.after(PhysicsSystems),
+                )
+                    .run_if(resource_exists::<LocalClient>),
+            );
+        }
+        build_render(app);
     }
 }
 
-/// Physics plugin for multiplayer games on client side
+/// Physics plugin for multiplayer games on server side
+///
+/// Use `PhysicsPluginBuilder` to change the settings.
 #[cfg(feature = "server")]
-pub struct PvwRRectPhysicsPluginServer {
-    pub spatial_grid_size: f32,
-}
+#[derive(Default)]
+pub struct PvwRRectPhysicsPluginServer(PhysicsPluginBuilder);
 
 #[cfg(feature = "server")]
-impl Default for PvwRRectPhysicsPluginServer {
-    fn default() -> Self {
-        Self {
-            spatial_grid_size: SpatialHashGrid::DEFAULT_CELL_SIZE,
-        }
+impl Plugin for PvwRRectPhysicsPluginServer {
+    fn build(&self, app: &mut App) {
+        assert_single_plugin(app, "PvwRRectPhysicsPluginServer", false, true);
+        app.add_plugins(PvwRRectPhysicsPluginHeadless(self.0.clone()));
     }
 }
 
-#[cfg(feature = "server")]
-impl Plugin for PvwRRectPhysicsPluginServer {
+/// Minimal plugin that only runs the simulation, without any rendering integration
+///
+/// Used by both `PvwRRectPhysicsPlugin` and `PvwRRectPhysicsPluginServer`.
+/// Use `PhysicsPluginBuilder` to change the settings.
+#[cfg(feature = "physics")]
+#[derive(Default)]
+pub struct PvwRRectPhysicsPluginHeadless(PhysicsPluginBuilder);
+
+#[cfg(feature = "physics")]
+impl Plugin for PvwRRectPhysicsPluginHeadless {
     fn build(&self, app: &mut App) {
+        assert_single_plugin(app, "PvwRRectPhysicsPluginHeadless", false, true);
         #[cfg(feature = "reflect")]
-        app.add_plugins(type_registry);
-        app.insert_resource(SpatialHashGrid {
-            cell_size: self.spatial_grid_size,
-            ..Default::default()
+        type_registry(app);
+        app.init_resource::<ServerSimulation>();
+
+        // the client plugin of a host added before already has the systems, only use these settings
+        #[cfg(feature = "render")]
+        if app.is_plugin_added::<PvwRRectPhysicsPluginClient>() {
+            insert_physics_resources(app, &self.0);
+            return;
+        }
+
+        build_physics(app, &self.0);
+    }
+}
+
+/// Inserted by the headless plugin, so a client plugin in the same app knows it runs on a host.
+#[cfg(feature = "physics")]
+#[derive(Resource, Default)]
+struct ServerSimulation;
+
+/// Panics if a plugin that adds the same systems was already added to the app.
+///
+/// The singleplayer and client plugins both sync `Transform`, and the singleplayer, server and
+/// headless plugins all run the simulation, so two of either would run every system twice. A
+/// client plugin and a server or headless plugin make a host, which is allowed. A plugin is only
+/// marked as added once it is built, so the headless plugin added by the singleplayer and server
+/// plugins does not trigger this.
+#[cfg(any(feature = "physics", feature = "render"))]
+fn assert_single_plugin(app: &App, plugin: &str, renders: bool, simulates: bool) {
+    let added = [
+        #[cfg(feature = "singleplayer")]
+        (
+            "PvwRRectPhysicsPlugin",
+            true,
+            true,
+            app.is_plugin_added::<PvwRRectPhysicsPlugin>(),
+        ),
+        #[cfg(feature = "render")]
+        (
+            "PvwRRectPhysicsPluginClient",
+            true,
+            false,
+            app.is_plugin_added::<PvwRRectPhysicsPluginClient>(),
+        ),
+        #[cfg(feature = "server")]
+        (
+            "PvwRRectPhysicsPluginServer",
+            false,
+            true,
+            app.is_plugin_added::<PvwRRectPhysicsPluginServer>(),
+        ),
+        #[cfg(feature = "physics")]
+        (
+            "PvwRRectPhysicsPluginHeadless",
+            false,
+            true,
+            app.is_plugin_added::<PvwRRectPhysicsPluginHeadless>(),
+        ),
+    ];
+
+    let duplicate = added
+        .iter()
+        .find(|(_, other_renders, other_simulates, added)| {
+            *added && ((renders && *other_renders) || (simulates && *other_simulates))
         });
-        app.add_message::<CollisionMessage>();
-        app.configure_sets(FixedUpdate, PhysicsSystems);
+    if let Some((other, ..)) = duplicate {
+        panic!(
+            "{plugin} cannot be added to an app that already has {other}, both would run the \
+             same systems. Use PvwRRectPhysicsPluginClient with the `physics` feature and a \
+             `LocalClient` resource for a client that also simulates, or add it together with \
+             PvwRRectPhysicsPluginServer for a host."
+        );
+    }
+}
+
+#[cfg(feature = "physics")]
+fn insert_physics_resources(app: &mut App, builder: &PhysicsPluginBuilder) {
+    if let Some(hz) = builder.tick_rate_hz {
+        app.insert_resource(Time::<Fixed>::from_hz(hz));
+    }
+    app.insert_resource(builder.config.clone());
+    app.insert_resource(SpatialHashGrid::with_hasher(builder.cell_size, FixedHasher));
+}
+
+#[cfg(feature = "physics")]
+fn build_physics(app: &mut App, builder: &PhysicsPluginBuilder) {
+    insert_physics_resources(app, builder);
+    app.add_message::<CollisionMessage>();
+    app.add_message::<PhysicsAnomaly>();
+    app.add_message::<ContainedWithin>();
+    app.add_message::<FrameCollisionMessage>();
+    app.add_message::<ForceClamped>();
+    app.add_message::<GroupOverlapStarted>();
+    app.add_message::<GroupOverlapEnded>();
+    app.add_message::<SensorOverlapStarted>();
+    app.add_message::<SensorOverlapEnded>();
+    app.add_message::<CellChanged>();
+    app.add_message::<PotentialContact>();
+    app.add_message::<JointBroken>();
+    app.add_message::<PairBudgetExceeded>();
+    app.add_message::<ColliderTypeChanged>();
+    app.add_message::<MovementBlocked>();
+    app.add_message::<DamageMessage>();
+    app.add_message::<HitboxMessage>();
+    app.add_observer(register_new_collider);
+    app.add_observer(unregister_removed_collider);
+    app.add_observer(joint::break_joints_of_removed_body);
+    app.add_observer(lod::snap_out_of_statics);
+    app.init_resource::<PhysicsFreeze>();
+    app.init_resource::<PhysicsGroups>();
+    app.init_resource::<Contacts>();
+    app.init_resource::<solver::StaticContacts>();
+    app.init_resource::<solver::SensorOverlaps>();
+    app.init_resource::<lod::LodSteps>();
+    app.configure_sets(
+        FixedUpdate,
+        PhysicsSystems.run_if(freeze::physics_not_frozen),
+    );
+    app.add_systems(
+        FixedUpdate,
+        (
+            (
+                tween_colliders,
+                validate_colliders,
+                apply_density,
+                track_collider_type_changes,
+                apply_force_presets,
+                avoidance::apply_avoidance,
+                lod::update_simulation_lod,
+                lod::skip_remote_bodies,
+                activation::skip_disabled_groups,
+                record_tick_start,
+            )
+                .chain(),
+            update_velocity_and_predict,
+            (wrap_positions, arena::keep_in_arena).chain(),
+            validate_motion,
+            detect_fast_bodies,
+            sync_rigid_groups,
+            carry_platform_riders,
+            hitbox::sync_hitboxes,
+            update_spatial_hash_grid,
+            exception::tick_collision_exceptions,
+            (
+                solver::check_collisions_and_resolve.run_if(not(solver::broadphase_only)),
+                solver::send_potential_contacts.run_if(solver::broadphase_only),
+                joint::solve_distance_joints.run_if(not(solver::broadphase_only)),
+                joint::solve_tethers.run_if(not(solver::broadphase_only)),
+            )
+                .chain(),
+            (wrap_positions, arena::keep_in_arena).chain(),
+            contact_graph::update_contacts,
+            count_contacts,
+            (update_sensor_groups, update_sensor_overlaps),
+            update_welds,
+            validate_motion,
+            update_linear_displacement,
+        )
+            .chain()
+            .in_set(PhysicsSystems),
+    );
+    #[cfg(feature = "metrics")]
+    {
+        app.init_resource::<telemetry::TickStartTime>();
         app.add_systems(
             FixedUpdate,
             (
-                update_velocity_and_predict,
-                update_spatial_hash_grid,
-                check_collisions_and_resolve,
-            )
-                .chain()
-                .in_set(PhysicsSystems),
+                telemetry::start_tick
+                    .before(tween_colliders)
+                    .in_set(PhysicsSystems),
+                telemetry::record_tick
+                    .after(update_linear_displacement)
+                    .in_set(PhysicsSystems),
+            ),
         );
     }
+    app.add_systems(
+        FixedUpdate,
+        (
+            damage::apply_contact_damage,
+            hitbox::send_hitbox_messages,
+            (
+                knockback::stop_knockback_at_walls,
+                knockback::decay_knockback,
+            )
+                .chain(),
+        )
+            .after(contact_graph::update_contacts)
+            .in_set(PhysicsSystems),
+    );
+    app.add_systems(FixedUpdate, freeze::count_down_freeze.after(PhysicsSystems));
+    app.add_systems(Update, coalesce_collision_messages);
 }
 
-#[cfg(feature = "reflect")]
+#[cfg(feature = "render")]
+fn build_render(app: &mut App) {
+    app.init_resource::<TileSize>();
+    app.init_resource::<PhysicsConfig>();
+    #[cfg(feature = "gizmos")]
+    app.init_resource::<PhysicsDebugConfig>();
+    app.add_systems(
+        Update,
+        (
+            update_translation,
+            #[cfg(feature = "camera-follow")]
+            camera::follow_camera_target.after(update_translation),
+            #[cfg(feature = "gizmos")]
+            debug::draw_hitboxes,
+            #[cfg(all(feature = "gizmos", feature = "physics"))]
+            debug::draw_grid,
+        ),
+    );
+    app.add_systems(PostUpdate, translation_just_added);
+    #[cfg(feature = "physics")]
+    app.add_systems(FixedUpdate, position_from_transform.before(PhysicsSystems));
+    #[cfg(not(feature = "physics"))]
+    app.add_systems(FixedUpdate, position_from_transform);
+}
+
+#[cfg(all(feature = "reflect", any(feature = "physics", feature = "render")))]
 fn type_registry(app: &mut App) {
     app.register_type::<Position>();
     app.register_type::<Movement>();
     app.register_type::<Collider>();
     app.register_type::<ColliderType>();
     app.register_type::<Force>();
+    app.register_type::<PartialForce>();
+    app.register_type::<ForceGroup>();
+    app.register_type::<DampingMode>();
+    app.register_type::<PushPriority>();
+    app.register_type::<Platform>();
+    app.register_type::<WeldedTo>();
+    app.register_type::<DistanceJoint>();
+    app.register_type::<TetherConstraint>();
+    app.register_type::<TetherAnchor>();
+    app.register_type::<ColliderTween>();
+    app.register_type::<RigidGroup>();
+    app.register_type::<Friction>();
+    app.register_type::<TrackContacts>();
+    app.register_type::<ContactCount>();
+    app.register_type::<LinearDisplacement>();
+    app.register_type::<DynamicBody>();
+    app.register_type::<StaticBody>();
+    app.register_type::<SensorBody>();
+    app.register_type::<SensorGroup>();
+    app.register_type::<StepUp>();
+    app.register_type::<WallSlide>();
+    app.register_type::<SpeculativeMargin>();
+    app.register_type::<Avoidance>();
+    app.register_type::<HitboxKind>();
+    app.register_type::<PhysicsGroup>();
+    app.register_type::<SpringFollower>();
+    app.register_type::<PredictionError>();
+    app.register_type::<RenderOffset>();
+    app.register_type::<NoInterpolation>();
+    app.register_type::<UserData>();
+    app.register_type::<SimulationAnchor>();
+    app.register_type::<SimplifiedPhysics>();
+    app.register_type::<SyncMode>();
+    app.register_type::<ZIndexMode>();
+    app.register_type::<Silent>();
+    app.register_type::<ActiveStatic>();
+    app.register_type::<Authority>();
+    app.register_type::<Density>();
+    app.register_type::<LocalClient>();
+    app.register_type::<Interpolation>();
+    app.register_type::<WorldTopology>();
+    app.register_type::<ArenaBounds>();
+    app.register_type::<BoundaryResponse>();
+    app.register_type::<LayerMask>();
+    app.register_type::<CollisionLayers>();
+    app.register_type::<Destructible>();
+    app.register_type::<ContactDamage>();
+    app.register_type::<ConstantForce>();
+    app.register_type::<SeekTarget>();
+    app.register_type::<FleePoint>();
+    app.register_type::<PhysicsConfig>();
+    app.register_type::<SimulationLod>();
+    app.register_type::<ContactData>();
+    app.register_type::<ContactSide>();
+    #[cfg(feature = "physics")]
+    app.register_type::<CollisionExceptions>();
+    #[cfg(feature = "gizmos")]
+    app.register_type::<PhysicsDebugConfig>();
+    #[cfg(feature = "camera-follow")]
+    app.register_type::<PhysicsCameraFollow>();
 }
 
-#[cfg(feature = "render")]
-#[derive(Debug, Resource, Clone, Copy)]
-pub struct TileSize(f32, Vec2);
+/// All simulation systems, running in `FixedUpdate`.
+///
+/// Order your own systems relative to this set, e.g. apply forces `.before(PhysicsSystems)`.
+#[cfg(feature = "physics")]
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PhysicsSystems;
 
-#[cfg(feature = "render")]
-impl Default for TileSize {
-    fn default() -> Self {
-        Self::new(8.0)
+/// Emitted every tick for each pair of overlapping colliders, sorted by `(entity_a, entity_b)`.
+///
+/// The contact data is seen from the first entity, e.g. `ContactSide::Bottom` means the second
+/// entity touched the bottom of the first.
+///
+/// Static colliders only find their own contacts with `ActiveStatic`, otherwise a pair is only
+/// reported when the other collider is not static. A moving body always comes before a static one.
+#[cfg(feature = "physics")]
+#[derive(Message, Event, Debug)]
+pub struct CollisionMessage(pub Entity, pub Entity, pub ContactData);
+
+/// Emitted once per frame for each pair that collided during any of the frame's physics ticks.
+///
+/// Several fixed ticks can run in one frame when the fixed timestep catches up after a hitch,
+/// each sending a `CollisionMessage` for the same pair. Use this message instead for effects that
+/// should only trigger once per frame, like impact sounds.
+#[cfg(feature = "physics")]
+#[derive(Message, Event, Debug)]
+pub struct FrameCollisionMessage {
+    pub a: Entity,
+    pub b: Entity,
+    /// Contact data of the last tick, as seen from `a`
+    pub contact: ContactData,
+    /// Number of ticks in which the pair collided during the frame
+    pub count: u32,
+}
+
+/// Emitted when an entity starts overlapping any sensor of a `SensorGroup`.
+#[cfg(feature = "physics")]
+#[derive(Message, Event, Debug, Clone)]
+pub struct GroupOverlapStarted(pub String, pub Entity);
+
+/// Emitted when an entity stops overlapping every sensor of a `SensorGroup`.
+#[cfg(feature = "physics")]
+#[derive(Message, Event, Debug, Clone)]
+pub struct GroupOverlapEnded(pub String, pub Entity);
+
+/// Emitted when an entity starts overlapping the sensor collider in the first field.
+///
+/// Sensors with a `SensorFilter` report their overlaps with `SensorMessage` instead.
+#[cfg(feature = "physics")]
+#[derive(Message, Event, Debug, Clone, Copy)]
+pub struct SensorOverlapStarted(pub Entity, pub Entity);
+
+/// Emitted when an entity stops overlapping the sensor collider in the first field, e.g. after
+/// either of them moved to other `CollisionLayers`.
+#[cfg(feature = "physics")]
+#[derive(Message, Event, Debug, Clone, Copy)]
+pub struct SensorOverlapEnded(pub Entity, pub Entity);
+
+/// Emitted every tick while the first entity's collider lies entirely inside the second's.
+///
+/// A `CollisionMessage` is sent for the pair as well.
+#[cfg(feature = "physics")]
+#[derive(Message, Event, Debug)]
+pub struct ContainedWithin(pub Entity, pub Entity);
+
+/// Emitted when the spatial grid cells an entity occupies change, if enabled with
+/// `PhysicsConfig::cell_change_messages`.
+///
+/// Useful for coarse reactions to movement like chunk loading or assigning AI sectors.
+/// Cells are `SpatialHashGrid::cell_size()` tiles wide.
+#[cfg(feature = "physics")]
+#[derive(Message, Event, Debug, Clone)]
+pub struct CellChanged {
+    pub entity: Entity,
+    pub old_cells: Vec<IVec2>,
+    pub new_cells: Vec<IVec2>,
+}
+
+/// Emitted every tick for each pair of colliders sharing a spatial grid cell, sorted by entity,
+/// when `PhysicsConfig::broadphase_only` is set.
+///
+/// Pairs the solver would skip, like two static colliders, members of the same rigid group,
+/// pairs with a `CollisionExceptions` entry or non-interacting `CollisionLayers`, are left out.
+/// The colliders may not overlap.
+#[cfg(feature = "physics")]
+#[derive(Message, Event, Debug, Clone, Copy)]
+pub struct PotentialContact(pub Entity, pub Entity);
+
+/// Emitted when a `DistanceJoint` is despawned because one of the entities it connects was
+/// despawned or lost its `Position`.
+#[cfg(feature = "physics")]
+#[derive(Message, Event, Debug, Clone, Copy)]
+pub struct JointBroken {
+    /// The joint entity, which no longer exists
+    pub joint: Entity,
+    pub a: Entity,
+    pub b: Entity,
+    /// Which of `a` and `b` was removed
+    pub removed: Entity,
+}
+
+/// Emitted when an entity is hit by a body with `ContactDamage`, with the damage it takes.
+#[cfg(feature = "physics")]
+#[derive(Message, Event, Debug, Clone, Copy)]
+pub struct DamageMessage(pub Entity, pub f32);
+
+/// Emitted every tick for each `HitboxKind::Hitbox` overlapping a `HitboxKind::Hurtbox` of
+/// another owner.
+///
+/// The contact data is seen from the hitbox.
+#[cfg(feature = "physics")]
+#[derive(Message, Event, Debug, Clone, Copy)]
+pub struct HitboxMessage {
+    pub hitbox: Entity,
+    pub hurtbox: Entity,
+    /// Owner of the hitbox, the parent it is welded to
+    pub attacker: Entity,
+    /// Owner of the hurtbox
+    pub target: Entity,
+    pub contact: ContactData,
+}
+
+/// Emitted when the `ColliderType` of an entity switches between static, dynamic and sensor,
+/// e.g. a door becoming passable. Changing only the mass of a dynamic collider does not count.
+///
+/// The new type is used by every physics system from the tick it was changed in: platforms and
+/// riders stop being carried and the friction of bodies that are no longer dynamic is reset.
+#[cfg(feature = "physics")]
+#[derive(Message, Event, Debug, Clone, Copy)]
+pub struct ColliderTypeChanged {
+    pub entity: Entity,
+    pub previous: ColliderType,
+    pub current: ColliderType,
+}
+
+/// Emitted when there were more broadphase pairs than `PhysicsConfig::max_pairs` during a tick.
+///
+/// Sent on the first tick over the budget and then at most once a second while it stays exceeded.
+/// Enable the `log` feature to also log a warning.
+#[cfg(feature = "physics")]
+#[derive(Message, Event, Debug, Clone, Copy)]
+pub struct PairBudgetExceeded {
+    /// Number of pairs found by the broadphase
+    pub pairs: usize,
+    /// Number of pairs that were not checked this tick
+    pub deferred: usize,
+}
+
+/// Emitted when static colliders stopped a dynamic body from covering at least half of the
+/// distance it tried to move during a tick, e.g. to repath or play a pushing animation.
+#[cfg(feature = "physics")]
+#[derive(Message, Event, Debug, Clone, Copy)]
+pub struct MovementBlocked {
+    pub entity: Entity,
+    /// Displacement from the velocity of the body
+    pub intended: Vec2,
+    /// Displacement after collisions were resolved
+    pub actual: Vec2,
+    /// The static collider that cancelled most of the displacement
+    pub blocker: Entity,
+}
+
+/// Emitted when a force starts being longer than its `Force::max_magnitude` or
+/// `PhysicsConfig::max_force`.
+///
+/// A force that stays too long is clamped every tick but only reported once, until it is back
+/// within the limit. Enable the `log` feature to also log a warning.
+#[cfg(feature = "physics")]
+#[derive(Message, Event, Debug, Clone)]
+pub struct ForceClamped {
+    pub entity: Entity,
+    pub id: String,
+    /// Length of the force before clamping
+    pub magnitude: f32,
+    pub limit: f32,
+}
+
+/// Emitted when a body does something the solver is likely to get wrong.
+///
+/// Enable the `log` feature to also log a warning for each anomaly.
+#[cfg(feature = "physics")]
+#[derive(Message, Event, Debug, Clone, Copy)]
+pub struct PhysicsAnomaly {
+    pub entity: Entity,
+    pub kind: PhysicsAnomalyKind,
+    /// Penetration depth of the contact, or 0 if not caused by a contact
+    pub penetration: f32,
+    /// Displacement of the body during the tick
+    pub speed: f32,
+}
+
+#[cfg(feature = "physics")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhysicsAnomalyKind {
+    /// Penetration exceeded half of the body's collider size
+    DeepPenetration,
+    /// Body moved more than one spatial grid cell in a single tick and may tunnel through others
+    Tunneling,
+}
+
+#[cfg(feature = "physics")]
+impl PhysicsAnomaly {
+    fn report(self, messages: &mut MessageWriter<PhysicsAnomaly>) {
+        #[cfg(feature = "log")]
+        bevy_log::warn!(
+            "physics anomaly {:?} on {}: penetration {}, speed {}",
+            self.kind,
+            self.entity,
+            self.penetration,
+            self.speed
+        );
+
+        messages.write(self);
     }
 }
 
-#[cfg(feature = "render")]
-impl TileSize {
-    pub fn new(size: f32) -> Self {
-        Self(size, Vec2::splat(size))
+/// Clamps colliders that were changed into an invalid state after spawning,
+/// e.g. by reflection or an inspector.
+#[cfg(feature = "physics")]
+fn validate_colliders(mut query: Query<(Entity, &mut Collider), Changed<Collider>>) {
+    for (entity, mut collider) in &mut query {
+        let Err(error) = collider.validate() else {
+            continue;
+        };
+
+        let clamped = collider.clamped();
+
+        #[cfg(feature = "log")]
+        bevy_log::warn!("invalid collider on {entity} repaired to {clamped:?}: {error}");
+        #[cfg(not(feature = "log"))]
+        let _ = (entity, error);
+
+        *collider.bypass_change_detection() = clamped;
     }
+}
+
+#[cfg(feature = "physics")]
+fn tween_colliders(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Collider, &mut ColliderTween)>,
+    time: Res<Time>,
+) {
+    for (entity, mut collider, mut tween) in &mut query {
+        tween.elapsed += time.delta_secs();
+
+        let resized = tween.apply(*collider);
+        if resized.size != collider.size || resized.radius != collider.radius {
+            *collider = resized;
+        }
 
-    pub fn size(&self) -> f32 {
-        self.0
+        if tween.is_finished() {
+            commands.entity(entity).remove::<ColliderTween>();
+        }
     }
+}
+
+/// Recomputes the mass of dynamic colliders with a `Density`.
+#[cfg(feature = "physics")]
+fn apply_density(
+    mut query: Query<(&mut Collider, &Density), Or<(Changed<Collider>, Changed<Density>)>>,
+) {
+    for (mut collider, density) in &mut query {
+        let ColliderType::Dynamic(mass) = collider.ctype else {
+            continue;
+        };
 
-    pub fn vec(&self) -> Vec2 {
-        self.1
+        // a zero area or density would make an invalid mass, keep the previous one
+        let new_mass = collider.area() * density.0;
+        if new_mass > 0.0 && new_mass.is_finite() && new_mass != mass {
+            collider.ctype = ColliderType::Dynamic(new_mass);
+        }
     }
 }
 
 #[cfg(feature = "physics")]
-#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
-struct PhysicsSystems;
+fn track_collider_type_changes(
+    mut query: Query<(Entity, &Collider, &mut Movement), Changed<Collider>>,
+    mut removed: RemovedComponents<Collider>,
+    mut known: Local<EntityHashMap<ColliderType>>,
+    mut changes: MessageWriter<ColliderTypeChanged>,
+) {
+    for entity in removed.read() {
+        known.remove(&entity);
+    }
+
+    for (entity, collider, mut movement) in &mut query {
+        let Some(previous) = known.insert(entity, collider.ctype) else {
+            continue;
+        };
+
+        if std::mem::discriminant(&previous) == std::mem::discriminant(&collider.ctype) {
+            continue;
+        }
+
+        // friction only comes from static contacts of dynamic bodies
+        if !matches!(collider.ctype, ColliderType::Dynamic(_)) && movement.friction != Vec2::ZERO {
+            movement.friction = Vec2::ZERO;
+        }
+
+        changes.write(ColliderTypeChanged {
+            entity,
+            previous,
+            current: collider.ctype,
+        });
+    }
+}
 
 #[cfg(feature = "physics")]
-#[derive(Message, Event, Debug)]
-pub struct CollisionMessage(pub Entity, pub Entity);
+fn apply_force_presets(
+    mut movements: Query<&mut Movement>,
+    colliders: Query<(Entity, &Collider)>,
+    config: Res<PhysicsConfig>,
+    constant: Query<(Entity, &ConstantForce)>,
+    seek: Query<(Entity, &SeekTarget, &Position)>,
+    flee: Query<(Entity, &FleePoint, &Position)>,
+    tethers: Query<(Entity, &TetherConstraint, &Position)>,
+    followers: Query<(Entity, &SpringFollower, &Position)>,
+    positions: Query<&Position>,
+    time: Res<Time>,
+    mut removed_constant: RemovedComponents<ConstantForce>,
+    mut removed_seek: RemovedComponents<SeekTarget>,
+    mut removed_flee: RemovedComponents<FleePoint>,
+    mut removed_tether: RemovedComponents<TetherConstraint>,
+    mut removed_follower: RemovedComponents<SpringFollower>,
+) {
+    for (entity, collider) in &colliders {
+        let Ok(mut movement) = movements.get_mut(entity) else {
+            continue;
+        };
+
+        if matches!(collider.ctype, ColliderType::Dynamic(_)) && config.gravity != Vec2::ZERO {
+            movement.apply_force(PartialForce {
+                id: PhysicsConfig::GRAVITY_FORCE.to_string(),
+                force: Some(config.gravity),
+                active: Some(true),
+                group: Some(ForceGroup::Environment),
+                ..Default::default()
+            });
+        } else if movement.forces.contains_key(PhysicsConfig::GRAVITY_FORCE) {
+            movement.forces.remove(PhysicsConfig::GRAVITY_FORCE);
+        }
+    }
+
+    // the spring force carries the follower's speed from tick to tick, so it is integrated here
+    let delta = time.delta_secs();
+    let mut springs = Vec::new();
+    for (entity, follower, pos) in &followers {
+        let (Ok(target_pos), Ok(target_movement), Ok(movement)) = (
+            positions.get(follower.target),
+            movements.get(follower.target),
+            movements.get(entity),
+        ) else {
+            continue;
+        };
+
+        if delta <= 0.0 {
+            continue;
+        }
+
+        let speed = movement
+            .forces
+            .get(SpringFollower::FORCE_ID)
+            .map_or(Vec2::ZERO, |force| force.force);
+        let target_speed = target_movement.velocity / delta;
+        let stretch = config
+            .topology
+            .delta(pos.0, target_pos.0 + follower.rest_offset);
+
+        let acceleration = stretch * follower.stiffness - (speed - target_speed) * follower.damping;
+        springs.push((entity, speed + acceleration * delta));
+    }
+
+    let mut apply = |entity: Entity, id: &str, group: ForceGroup, force: Vec2| {
+        if let Ok(mut movement) = movements.get_mut(entity) {
+            movement.apply_force(PartialForce {
+                id: id.to_string(),
+                force: Some(force),
+                active: Some(true),
+                group: Some(group),
+                ..Default::default()
+            });
+        }
+    };
+
+    for (entity, constant) in &constant {
+        apply(
+            entity,
+            ConstantForce::FORCE_ID,
+            ConstantForce::GROUP,
+            constant.0,
+        );
+    }
+
+    for (entity, seek, pos) in &seek {
+        let force = match positions.get(seek.entity) {
+            Ok(target) => (target.0 - pos.0).normalize_or_zero() * seek.strength,
+            Err(_) => Vec2::ZERO,
+        };
+
+        apply(entity, SeekTarget::FORCE_ID, SeekTarget::GROUP, force);
+    }
+
+    for (entity, flee, pos) in &flee {
+        apply(
+            entity,
+            FleePoint::FORCE_ID,
+            FleePoint::GROUP,
+            (pos.0 - flee.point).normalize_or_zero() * flee.strength,
+        );
+    }
+
+    for (entity, tether, pos) in &tethers {
+        let anchor = match tether.anchor {
+            TetherAnchor::Point(point) => Some(point),
+            TetherAnchor::Entity(anchor) => positions.get(anchor).ok().map(|anchor| anchor.0),
+        };
+
+        let force = anchor.map_or(Vec2::ZERO, |anchor| {
+            let offset = config.topology.delta(pos.0, anchor);
+            let stretch = offset.length() - tether.max_length * TetherConstraint::SPRING_START;
+            offset.normalize_or_zero() * stretch.max(0.0) * tether.stiffness
+        });
+
+        apply(
+            entity,
+            TetherConstraint::FORCE_ID,
+            TetherConstraint::GROUP,
+            force,
+        );
+    }
+
+    for (entity, force) in springs {
+        apply(
+            entity,
+            SpringFollower::FORCE_ID,
+            SpringFollower::GROUP,
+            force,
+        );
+    }
+
+    let removed = removed_constant
+        .read()
+        .map(|e| (e, ConstantForce::FORCE_ID))
+        .chain(removed_seek.read().map(|e| (e, SeekTarget::FORCE_ID)))
+        .chain(removed_flee.read().map(|e| (e, FleePoint::FORCE_ID)))
+        .chain(
+            removed_tether
+                .read()
+                .map(|e| (e, TetherConstraint::FORCE_ID)),
+        )
+        .chain(
+            removed_follower
+                .read()
+                .map(|e| (e, SpringFollower::FORCE_ID)),
+        );
+
+    for (entity, id) in removed {
+        if let Ok(mut movement) = movements.get_mut(entity) {
+            movement.forces.remove(id);
+        }
+    }
+}
 
 #[cfg(feature = "physics")]
 fn update_velocity_and_predict(
-    mut query: Query<(&mut Movement, &mut Position)>,
+    mut query: Query<(Entity, &mut Movement, &mut Position)>,
+    mut clamped: MessageWriter<ForceClamped>,
+    // ids of the forces of each body that were clamped the last time it was simulated
+    mut clamping: Local<HashMap<Entity, HashSet<String>>>,
     time: Res<Time<Fixed>>,
+    config: Res<PhysicsConfig>,
+    lod_steps: Res<lod::LodSteps>,
 ) {
-    let dt = time.delta_secs();
+    clamping.retain(|entity, _| query.contains(*entity));
+
+    for (entity, mut vel, mut pos) in &mut query {
+        let steps = lod_steps.steps(entity);
+        if steps == 0 {
+            continue;
+        }
 
-    for (mut vel, mut pos) in &mut query {
+        let was_clamping = clamping.remove(&entity).unwrap_or_default();
+        let mut now_clamping = HashSet::new();
+
+        // bodies simulated at a reduced rate catch up on the ticks they skipped
+        let dt = time.delta_secs() * steps as f32;
+        let vel = &mut *vel;
         let mut total = Vec2::ZERO;
 
-        let lerp_val = vel.damping * dt;
+        let damping = vel.damping + vel.friction;
+        let lerp_val = match vel.damping_mode {
+            DampingMode::Linear => {
+                // past 2 a force would flip to a longer one every tick, so it is stopped instead
+                let lerp_val = (damping * dt).max(Vec2::ZERO);
+                Vec2::select(lerp_val.cmpgt(Vec2::splat(2.0)), Vec2::ONE, lerp_val)
+            },
+            DampingMode::Exponential => {
+                let keep = (Vec2::ONE - damping.clamp(Vec2::ZERO, Vec2::ONE)).powf(dt);
+                Vec2::ONE - keep
+            },
+        };
         for (_, force) in &mut vel.forces {
+            if vel.paused_groups.contains(&force.group) {
+                if was_clamping.contains(&force.id) {
+                    now_clamping.insert(force.id.clone());
+                }
+                continue;
+            }
+
             if !force.active {
                 force.force.x = force.force.x.lerp(0.0, lerp_val.x);
                 force.force.y = force.force.y.lerp(0.0, lerp_val.y);
             }
 
+            let limit = match (force.max_magnitude, config.max_force) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            };
+
+            if let Some(limit) = limit {
+                let magnitude = force.force.length();
+                if magnitude > limit {
+                    // only report when clamping starts, not every tick the force stays too long
+                    if !was_clamping.contains(&force.id) {
+                        #[cfg(feature = "log")]
+                        bevy_log::warn!(
+                            "force \"{}\" on {} clamped from {} to {}",
+                            force.id,
+                            entity,
+                            magnitude,
+                            limit
+                        );
+
+                        clamped.write(ForceClamped {
+                            entity,
+                            id: force.id.clone(),
+                            magnitude,
+                            limit,
+                        });
+                    }
+
+                    now_clamping.insert(force.id.clone());
+                    force.force = force.force.clamp_length_max(limit);
+                }
+            }
+
             total += force.force;
         }
 
+        if !now_clamping.is_empty() {
+            clamping.insert(entity, now_clamping);
+        }
+
         vel.velocity = total.clamp_length_max(Movement::MAX_VELOCITY) * dt;
 
         pos.0 += vel.velocity;
     }
 }
 
+/// Guards against NaN and infinity spreading from a bad force or mass into positions
+/// and spatial grid keys.
+#[cfg(feature = "physics")]
+fn validate_motion(
+    mut query: Query<(Entity, &mut Position, &mut Movement)>,
+    config: Res<PhysicsConfig>,
+    mut last_good: Local<HashMap<Entity, Vec2>>,
+) {
+    let policy = config.validate;
+    if policy == ValidationPolicy::Off {
+        last_good.clear();
+        return;
+    }
+
+    let mut good = HashMap::with_capacity(last_good.len());
+
+    for (entity, mut pos, mut movement) in &mut query {
+        let bad_force = movement
+            .forces
+            .iter()
+            .find(|(_, force)| !force.force.is_finite())
+            .map(|(id, force)| (id.clone(), force.force));
+
+        if pos.0.is_finite() && movement.velocity.is_finite() && bad_force.is_none() {
+            good.insert(entity, pos.0);
+            continue;
+        }
+
+        match policy {
+            ValidationPolicy::Off => {},
+            ValidationPolicy::Clamp => {
+                let zero_non_finite = |v: Vec2| {
+                    Vec2::new(
+                        if v.x.is_finite() { v.x } else { 0.0 },
+                        if v.y.is_finite() { v.y } else { 0.0 },
+                    )
+                };
+
+                pos.0 = zero_non_finite(pos.0);
+                movement.velocity = zero_non_finite(movement.velocity);
+                for force in movement.forces.values_mut() {
+                    force.force = zero_non_finite(force.force);
+                }
+
+                good.insert(entity, pos.0);
+            },
+            ValidationPolicy::ResetToLastGood => {
+                let last = last_good.get(&entity).copied().unwrap_or(Vec2::ZERO);
+
+                pos.0 = last;
+                movement.velocity = Vec2::ZERO;
+                movement.forces.retain(|_, force| force.force.is_finite());
+
+                good.insert(entity, last);
+            },
+            ValidationPolicy::Panic => match bad_force {
+                Some((id, force)) => {
+                    panic!("entity {entity} has non-finite force \"{id}\": {force}")
+                },
+                None => panic!(
+                    "entity {entity} has non-finite position {} or velocity {}",
+                    pos.0, movement.velocity
+                ),
+            },
+        }
+    }
+
+    *last_good = good;
+}
+
+#[cfg(feature = "physics")]
+fn detect_fast_bodies(
+    mut anomalies: MessageWriter<PhysicsAnomaly>,
+    query: Query<(Entity, &Movement)>,
+    spatial_grid: Res<SpatialHashGrid>,
+) {
+    for (entity, movement) in &query {
+        let speed = movement.velocity.length();
+        if speed > spatial_grid.cell_size {
+            PhysicsAnomaly {
+                entity,
+                kind: PhysicsAnomalyKind::Tunneling,
+                penetration: 0.0,
+                speed,
+            }
+            .report(&mut anomalies);
+        }
+    }
+}
+
+/// Moves rigid group members along with their root after the root was integrated.
+#[cfg(feature = "physics")]
+fn sync_rigid_groups(
+    mut members: Query<(&RigidGroup, &mut Position, &mut Movement)>,
+    roots: Query<(&Position, &Movement), Without<RigidGroup>>,
+) {
+    for (group, mut pos, mut movement) in &mut members {
+        if let Ok((root_pos, root_movement)) = roots.get(group.root) {
+            pos.0 = root_pos.0 + group.offset;
+            movement.velocity = root_movement.velocity;
+        }
+    }
+}
+
+#[cfg(feature = "physics")]
+fn carry_platform_riders(
+    mut platforms: Query<(&Position, &mut Platform, Option<&Collider>)>,
+    mut riders: Query<(&mut Position, &Collider), Without<Platform>>,
+) {
+    for (pos, mut platform, collider) in &mut platforms {
+        platform.displacement = match platform.last_position {
+            Some(last) => pos.0 - last,
+            None => Vec2::ZERO,
+        };
+        platform.last_position = Some(pos.0);
+
+        // riders are from the last tick, the platform may not be static anymore
+        let is_static =
+            collider.is_some_and(|collider| matches!(collider.ctype, ColliderType::Static));
+        if platform.displacement == Vec2::ZERO || !is_static {
+            continue;
+        }
+
+        for &rider in &platform.riders {
+            if let Ok((mut rider_pos, rider_collider)) = riders.get_mut(rider)
+                && matches!(rider_collider.ctype, ColliderType::Dynamic(_))
+            {
+                rider_pos.0 += platform.displacement;
+            }
+        }
+    }
+}
+
+/// Inserts colliders into the grid as soon as they are spawned,
+/// so they are visible to spatial queries before the next physics tick.
+#[cfg(feature = "physics")]
+fn register_new_collider(
+    add: On<Add, Collider>,
+    query: Query<(&Position, &Collider, Has<ActiveStatic>)>,
+    mut spatial_grid: ResMut<SpatialHashGrid>,
+) {
+    if let Ok((pos, coll, active)) = query.get(add.entity) {
+        let passive_static = matches!(coll.ctype, ColliderType::Static) && !active;
+        spatial_grid.insert_or_update(
+            add.entity,
+            pos,
+            coll,
+            Vec2::ZERO,
+            Vec2::ZERO,
+            passive_static,
+        );
+    }
+}
+
+#[cfg(feature = "physics")]
+fn unregister_removed_collider(
+    remove: On<Remove, Collider>,
+    mut spatial_grid: ResMut<SpatialHashGrid>,
+) {
+    spatial_grid.remove(remove.entity);
+}
+
+/// Warns once when a collider is spawned in an app without a physics plugin, where it would
+/// never move or collide.
+#[cfg(all(feature = "physics", feature = "log"))]
+fn warn_without_plugin(world: bevy_ecs::world::DeferredWorld, _: bevy_ecs::lifecycle::HookContext) {
+    static WARNED: std::sync::Once = std::sync::Once::new();
+
+    if !world.contains_resource::<SpatialHashGrid>() {
+        WARNED.call_once(|| {
+            bevy_log::warn!(
+                "a Collider was spawned in an app without a pvw_rrect_physics plugin, add \
+                 PvwRRectPhysicsPlugin, PvwRRectPhysicsPluginServer or \
+                 PvwRRectPhysicsPluginHeadless to simulate it"
+            );
+        });
+    }
+}
+
 #[cfg(feature = "physics")]
 fn update_spatial_hash_grid(
     mut spatial_grid: ResMut<SpatialHashGrid>,
-    query: Query<(Entity, &Position, &Collider)>,
+    mut cell_changes: MessageWriter<CellChanged>,
+    query: Query<
+        (
+            Entity,
+            &Position,
+            &Movement,
+            &Collider,
+            Option<&SpeculativeMargin>,
+            Option<&PhysicsGroup>,
+            Has<ActiveStatic>,
+        ),
+        Without<SimplifiedPhysics>,
+    >,
+    config: Res<PhysicsConfig>,
+    groups: Res<PhysicsGroups>,
 ) {
+    spatial_grid.topology = config.topology;
+
     let mut ent_list = HashSet::new();
-    for (ent, pos, coll) in &query {
+    for (ent, pos, movement, coll, margin, group, active) in &query {
+        // disabled bodies are removed from the grid below
+        if !groups.is_active(group) {
+            continue;
+        }
+
         ent_list.insert(ent);
-        spatial_grid.insert_or_update(ent, pos, coll);
+        let passive_static = matches!(coll.ctype, ColliderType::Static) && !active;
+        let ahead = margin.map_or(Vec2::ZERO, |margin| movement.velocity * margin.0.max(0.0));
+        let old_cells =
+            spatial_grid.insert_or_update(ent, pos, coll, movement.velocity, ahead, passive_static);
+
+        if config.cell_change_messages
+            && let Some(old_cells) = old_cells
+        {
+            cell_changes.write(CellChanged {
+                entity: ent,
+                old_cells: old_cells.into_iter().collect(),
+                new_cells: spatial_grid.ent_to_grid[&ent].iter().copied().collect(),
+            });
+        }
     }
 
     let mut to_remove = Vec::new();
@@ -222,157 +1472,358 @@ fn update_spatial_hash_grid(
     for ent in to_remove {
         spatial_grid.remove(ent);
     }
+
+    spatial_grid.refresh_pairs();
 }
 
 #[cfg(feature = "physics")]
-fn check_collisions_and_resolve(
-    mut messages: MessageWriter<CollisionMessage>,
-    mut query: Query<(&mut Position, &Collider, Entity)>,
-    spatial_grid: Res<SpatialHashGrid>,
+fn count_contacts(
+    mut messages: MessageReader<CollisionMessage>,
+    mut query: Query<(Entity, &mut ContactCount), With<TrackContacts>>,
 ) {
-    let len = query.iter().len();
-    let mut detection_data = HashMap::with_capacity(len);
-    let mut dynamic_positions = HashMap::with_capacity(len);
+    let mut counts: HashMap<Entity, u32> = HashMap::new();
+    for CollisionMessage(a, b, _) in messages.read() {
+        *counts.entry(*a).or_default() += 1;
+        *counts.entry(*b).or_default() += 1;
+    }
 
-    for (pos, coll, ent) in query.iter() {
-        detection_data.insert(ent, (*pos, *coll));
-        if matches!(coll.ctype, ColliderType::Dynamic(_)) {
-            dynamic_positions.insert(ent, pos.0);
-        }
+    for (entity, mut count) in &mut query {
+        let new_count = ContactCount(counts.get(&entity).copied().unwrap_or(0));
+        count.set_if_neq(new_count);
+    }
+}
+
+#[cfg(feature = "physics")]
+fn record_tick_start(mut query: Query<(&Position, &mut components::TickStart)>) {
+    for (pos, mut start) in &mut query {
+        start.0 = pos.0;
+    }
+}
+
+#[cfg(feature = "physics")]
+fn update_linear_displacement(
+    mut query: Query<(&Position, &components::TickStart, &mut LinearDisplacement)>,
+    config: Res<PhysicsConfig>,
+) {
+    for (pos, start, mut displacement) in &mut query {
+        let moved = config.topology.delta(start.0, pos.0);
+        displacement.set_if_neq(LinearDisplacement(moved));
     }
+}
 
-    let mut checked = HashSet::with_capacity(len * 2);
+#[cfg(feature = "physics")]
+fn wrap_positions(
+    mut query: Query<(&mut Position, Option<&PhysicsGroup>)>,
+    config: Res<PhysicsConfig>,
+    groups: Res<PhysicsGroups>,
+) {
+    if config.topology == WorldTopology::Plane {
+        return;
+    }
 
-    for (&entity_a, &(mut pos_a, collider_a)) in &detection_data {
-        // Optimisation hack for tilemaps
-        if matches!(collider_a.ctype, ColliderType::Static) {
+    for (mut pos, group) in &mut query {
+        if !groups.is_active(group) {
             continue;
         }
 
-        let Some(neighbors) = spatial_grid.iter(entity_a) else {
+        let wrapped = config.topology.wrap(pos.0);
+        if wrapped != pos.0 {
+            pos.0 = wrapped;
+        }
+    }
+}
+
+#[cfg(feature = "physics")]
+fn update_sensor_groups(
+    mut messages: MessageReader<CollisionMessage>,
+    mut started: MessageWriter<GroupOverlapStarted>,
+    mut ended: MessageWriter<GroupOverlapEnded>,
+    groups: Query<&SensorGroup>,
+    mut overlapping: Local<HashSet<(String, Entity)>>,
+) {
+    let mut current = HashSet::new();
+
+    for CollisionMessage(a, b, _) in messages.read() {
+        let group_a = groups.get(*a).ok();
+        let group_b = groups.get(*b).ok();
+
+        // sensors of the same group overlapping each other
+        if group_a.is_some() && group_a == group_b {
             continue;
-        };
+        }
 
-        if let Some(pos) = dynamic_positions.get(&entity_a) {
-            pos_a.0 = *pos;
+        if let Some(group) = group_a {
+            current.insert((group.0.clone(), *b));
         }
 
-        for &entity_b in neighbors.iter() {
-            if entity_a == entity_b {
-                continue;
-            }
+        if let Some(group) = group_b {
+            current.insert((group.0.clone(), *a));
+        }
+    }
 
-            let pair = if entity_a < entity_b {
-                (entity_a, entity_b)
-            } else {
-                (entity_b, entity_a)
-            };
+    for (group, entity) in current.difference(&overlapping) {
+        started.write(GroupOverlapStarted(group.clone(), *entity));
+    }
 
-            if !checked.insert(pair) {
-                continue;
-            }
+    for (group, entity) in overlapping.difference(&current) {
+        ended.write(GroupOverlapEnded(group.clone(), *entity));
+    }
 
-            let Some(&(mut pos_b, collider_b)) = detection_data.get(&entity_b) else {
-                continue;
-            };
+    *overlapping = current;
+}
 
-            if let Some(pos) = dynamic_positions.get(&entity_b) {
-                pos_b.0 = *pos;
-            }
+#[cfg(feature = "physics")]
+fn update_sensor_overlaps(
+    mut started: MessageWriter<SensorOverlapStarted>,
+    mut ended: MessageWriter<SensorOverlapEnded>,
+    sensor_overlaps: Res<solver::SensorOverlaps>,
+    mut overlapping: Local<HashSet<(Entity, Entity)>>,
+) {
+    // taken from the solver rather than `CollisionMessage`, which has no messages for the pairs
+    // the solver did not check during a tick
+    let current = &sensor_overlaps.0;
 
-            let offset = pos_b.0 - pos_a.0;
-            let offset_abs = offset.abs();
+    let mut new_overlaps: Vec<_> = current.difference(&overlapping).copied().collect();
+    let mut old_overlaps: Vec<_> = overlapping.difference(current).copied().collect();
+    new_overlaps.sort_unstable();
+    old_overlaps.sort_unstable();
 
-            let avg_size = (collider_a.size + collider_b.size) * 0.5;
+    started.write_batch(
+        new_overlaps
+            .into_iter()
+            .map(|(sensor, entity)| SensorOverlapStarted(sensor, entity)),
+    );
+    ended.write_batch(
+        old_overlaps
+            .into_iter()
+            .map(|(sensor, entity)| SensorOverlapEnded(sensor, entity)),
+    );
 
-            // check AABB collision
-            if offset_abs.x >= avg_size.x || offset_abs.y >= avg_size.y {
-                continue;
-            }
+    overlapping.clone_from(current);
+}
 
-            let mtv: Vec2;
-            let radii = collider_a.radius + collider_b.radius;
-            let dist = offset_abs - avg_size + radii;
+#[cfg(feature = "physics")]
+fn coalesce_collision_messages(
+    mut messages: MessageReader<CollisionMessage>,
+    mut frame_messages: MessageWriter<FrameCollisionMessage>,
+) {
+    let mut index: HashMap<(Entity, Entity), usize> = HashMap::new();
+    let mut coalesced: Vec<FrameCollisionMessage> = Vec::new();
 
-            // check inner AABB collision
-            if dist.x < 0.0 || dist.y < 0.0 {
-                let overlap = avg_size - offset_abs;
+    for CollisionMessage(a, b, contact) in messages.read() {
+        let pair = if a < b { (*a, *b) } else { (*b, *a) };
 
-                if overlap.x < overlap.y {
-                    mtv = Vec2::new(overlap.x * offset.x.signum(), 0.0);
+        match index.get(&pair) {
+            Some(&i) => {
+                let message = &mut coalesced[i];
+                message.count += 1;
+                message.contact = if message.a == *a {
+                    *contact
                 } else {
-                    mtv = Vec2::new(0.0, overlap.y * offset.y.signum());
-                }
-            } else {
-                // check corners
-                let dist_sq = dist.length_squared();
-                if dist_sq > radii * radii {
-                    continue;
-                }
+                    contact.flip()
+                };
+            },
+            None => {
+                index.insert(pair, coalesced.len());
+                coalesced.push(FrameCollisionMessage {
+                    a: *a,
+                    b: *b,
+                    contact: *contact,
+                    count: 1,
+                });
+            },
+        }
+    }
 
-                let dist_length = dist_sq.sqrt();
-                mtv = (dist / dist_length) * (radii - dist_length) * offset.signum();
-            }
+    frame_messages.write_batch(coalesced);
+}
+
+#[cfg(feature = "physics")]
+fn update_welds(
+    mut welded: Query<(Entity, &WeldedTo, &mut Movement, Option<&PhysicsGroup>)>,
+    mut positions: Query<&mut Position>,
+    groups: Res<PhysicsGroups>,
+) {
+    let welds: HashMap<Entity, WeldedTo> = welded.iter().map(|(e, weld, ..)| (e, *weld)).collect();
 
-            messages.write(CollisionMessage(entity_a, entity_b));
+    for (entity, _, mut movement, group) in &mut welded {
+        if !groups.is_active(group) {
+            continue;
+        }
 
-            match (collider_a.ctype, collider_b.ctype) {
-                // resolve collision by pushing one of the collider away
-                (ColliderType::Dynamic(_), ColliderType::Static) => {
-                    *dynamic_positions.entry(entity_a).or_insert(pos_a.0) -= mtv;
-                },
+        // follow the chain of welds up to the root so welds onto welded entities work too
+        let mut offset = Vec2::ZERO;
+        let mut current = entity;
+        let mut depth = 0;
 
-                // in this case we push both away based on their masses
-                (ColliderType::Dynamic(mass_a), ColliderType::Dynamic(mass_b)) => {
-                    let total_mass = mass_a + mass_b;
-                    let mass_share_a = mass_a / total_mass;
-                    let mass_share_b = mass_b / total_mass;
+        while let Some(weld) = welds.get(&current) {
+            offset += weld.offset;
+            current = weld.parent;
+            depth += 1;
 
-                    *dynamic_positions.entry(entity_a).or_insert(pos_a.0) -= mtv * mass_share_b;
-                    *dynamic_positions.entry(entity_b).or_insert(pos_b.0) += mtv * mass_share_a;
-                },
-                _ => {},
+            // welds form a cycle
+            if depth > welds.len() {
+                break;
             }
         }
-    }
 
-    for (mut next_pos, _, entity) in &mut query {
-        if let Some(new_pos_vec) = dynamic_positions.get(&entity) {
-            next_pos.0 = *new_pos_vec;
+        let Ok(root_pos) = positions.get(current).map(|pos| pos.0) else {
+            continue;
+        };
+
+        if let Ok(mut pos) = positions.get_mut(entity) {
+            let new_pos = root_pos + offset;
+            movement.velocity = new_pos - pos.0;
+            pos.0 = new_pos;
         }
     }
 }
 
 #[cfg(feature = "render")]
 fn translation_just_added(
-    mut query: Query<(&mut Transform, &Position), Or<(Added<Transform>, Added<Position>)>>,
+    mut query: Query<
+        (
+            &mut Transform,
+            &mut Position,
+            Option<&SyncMode>,
+            Option<&ZIndexMode>,
+            Option<&RenderOffset>,
+        ),
+        Or<(Added<Transform>, Added<Position>)>,
+    >,
     tile_size: Res<TileSize>,
+    config: Res<PhysicsConfig>,
 ) {
-    let size = tile_size.size();
-    for (mut transf, pos) in &mut query {
-        transf.translation = vec3(pos.0.x * size, pos.0.y * size, transf.translation.z);
+    for (mut transf, mut pos, sync_mode, z_index, render_offset) in &mut query {
+        let render_offset = render_offset.map_or(Vec2::ZERO, |offset| offset.0);
+
+        if sync_mode == Some(&SyncMode::FromTransform) {
+            let drawn = tile_size.to_physics(WorldPos::from_translation(transf.translation));
+            pos.0 = drawn.0 - render_offset;
+        } else {
+            let mut drawn = tile_size.to_world(PhysicsPos(pos.0 + render_offset));
+            if config.pixel_snap {
+                drawn.0 = drawn.0.round();
+            }
+            transf.translation = drawn.extend(transf.translation.z);
+        }
+
+        if let Some(z_index) = z_index {
+            transf.translation.z = z_index.z(pos.0);
+        }
     }
 }
 
 #[cfg(feature = "render")]
-fn update_translation(mut query: Query<(&mut Transform, &Position)>, tile_size: Res<TileSize>) {
-    let size = tile_size.size();
-    for (mut transf, pos) in &mut query {
-        transf.translation = vec3(pos.0.x * size, pos.0.y * size, transf.translation.z);
+fn position_from_transform(
+    mut query: Query<(&mut Position, &Transform, &SyncMode, Option<&RenderOffset>)>,
+    tile_size: Res<TileSize>,
+) {
+    for (mut pos, transf, sync_mode, render_offset) in &mut query {
+        if *sync_mode == SyncMode::FromTransform {
+            let drawn = tile_size.to_physics(WorldPos::from_translation(transf.translation));
+            pos.0 = drawn.0 - render_offset.map_or(Vec2::ZERO, |offset| offset.0);
+        }
     }
 }
 
-#[cfg(feature = "gizmos")]
-fn draw_hitboxes(
-    mut gizmos: Gizmos,
-    query: Query<(&Collider, &Position)>,
+#[cfg(feature = "render")]
+fn update_translation(
+    mut query: Query<(
+        Entity,
+        &mut Transform,
+        &Position,
+        Option<&Authority>,
+        Option<&SyncMode>,
+        Option<&ZIndexMode>,
+        Option<&PredictionError>,
+        Option<&RenderOffset>,
+        Has<NoInterpolation>,
+    )>,
     tile_size: Res<TileSize>,
+    config: Res<PhysicsConfig>,
+    local_client: Option<Res<LocalClient>>,
+    time: Res<Time>,
+    // unrounded translations when pixel snapping, so interpolation does not stall below a pixel
+    mut smooth: Local<EntityHashMap<Vec2>>,
 ) {
-    let size = tile_size.size();
-    for (collider, pos) in &query {
-        const HITBOX_COLOR: Color = Color::srgb(0.0, 1.0, 0.0);
-        gizmos
-            .rounded_rect_2d(pos.0 * size, collider.size * size, HITBOX_COLOR)
-            .corner_radius(collider.radius * size);
+    let local_t = match config.interpolation {
+        Interpolation::None => 1.0,
+        Interpolation::Lerp(t) => t.clamp(0.0, 1.0),
+    };
+
+    // the remote fraction is per 1/60 s, so remote bodies follow at the same speed at any frame rate
+    let remote = local_client.as_ref().map(|local_client| {
+        let rate = local_client.remote_interpolation.clamp(0.0, 1.0);
+        if rate >= 1.0 {
+            1.0
+        } else {
+            1.0 - (1.0 - rate).powf(time.delta_secs() * 60.0)
+        }
+    });
+
+    let mut next_smooth = EntityHashMap::default();
+
+    for (
+        entity,
+        mut transf,
+        pos,
+        authority,
+        sync_mode,
+        z_index,
+        error,
+        render_offset,
+        no_interpolation,
+    ) in &mut query
+    {
+        if let Some(z_index) = z_index {
+            transf.translation.z = z_index.z(pos.0);
+        }
+
+        if sync_mode == Some(&SyncMode::FromTransform) {
+            continue;
+        }
+
+        let mut t = local_t;
+
+        // remote bodies only move when a network update arrives, smooth out the jumps
+        if let (Some(local_client), Some(remote)) = (&local_client, remote) {
+            let authority = authority.copied().unwrap_or_default();
+            if !authority.is_local(Some(local_client.id)) {
+                t = remote;
+            }
+        }
+
+        if no_interpolation {
+            t = 1.0;
+        }
+
+        let offset = error.map_or(Vec2::ZERO, |error| error.offset)
+            + render_offset.map_or(Vec2::ZERO, |offset| offset.0);
+        let target = tile_size.to_world(PhysicsPos(pos.0 + offset));
+        let smoothed = smooth.get(&entity).filter(|_| config.pixel_snap);
+        let current = smoothed.map_or_else(
+            || WorldPos::from_translation(transf.translation),
+            |smoothed| WorldPos(*smoothed),
+        );
+
+        // a body that wrapped around a torus appears at the other edge instead of sliding there
+        // across the whole world
+        if let WorldTopology::Torus { size } = config.topology {
+            let jump = tile_size.to_physics(target).0 - tile_size.to_physics(current).0;
+            if jump.abs().cmpgt(size * 0.5).any() {
+                t = 1.0;
+            }
+        }
+
+        let next = current.0.lerp(target.0, t);
+        if config.pixel_snap {
+            next_smooth.insert(entity, next);
+            transf.translation = next.round().extend(transf.translation.z);
+        } else {
+            transf.translation = WorldPos(next).extend(transf.translation.z);
+        }
     }
+
+    *smooth = next_smooth;
 }
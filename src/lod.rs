@@ -0,0 +1,180 @@
+use crate::*;
+use bevy_ecs::prelude::*;
+use bevy_math::prelude::*;
+use bevy_platform::collections::HashMap;
+
+/// Number of ticks each body integrates during the current tick.
+#[derive(Resource, Default)]
+pub(crate) struct LodSteps {
+    /// Only bodies that do not integrate exactly one tick are stored
+    steps: HashMap<Entity, u32>,
+    skipped: HashMap<Entity, u32>,
+    /// Positions of every `SimulationAnchor` at the start of the tick
+    anchors: Vec<Vec2>,
+}
+
+impl LodSteps {
+    pub(crate) fn steps(&self, entity: Entity) -> u32 {
+        self.steps.get(&entity).copied().unwrap_or(1)
+    }
+
+    pub(crate) fn anchors(&self) -> &[Vec2] {
+        &self.anchors
+    }
+
+    /// Stops `entity` from integrating during the current tick.
+    pub(crate) fn skip(&mut self, entity: Entity) {
+        self.steps.insert(entity, 0);
+    }
+
+    /// Number of bodies that are not integrated during the current tick.
+    #[cfg(feature = "metrics")]
+    pub(crate) fn skipped_count(&self) -> usize {
+        self.steps.values().filter(|steps| **steps == 0).count()
+    }
+}
+
+pub(crate) fn update_simulation_lod(
+    mut commands: Commands,
+    mut lod_steps: ResMut<LodSteps>,
+    config: Res<PhysicsConfig>,
+    anchors: Query<&Position, With<SimulationAnchor>>,
+    bodies: Query<(Entity, &Position, Option<&Collider>, Has<SimplifiedPhysics>), With<Movement>>,
+) {
+    let lod_steps = &mut *lod_steps;
+    lod_steps.steps.clear();
+    lod_steps.anchors.clear();
+    lod_steps
+        .anchors
+        .extend(anchors.iter().map(|anchor| anchor.0));
+
+    let Some(lod) = config.lod.filter(|_| !anchors.is_empty()) else {
+        lod_steps.skipped.clear();
+        return;
+    };
+
+    let mut skipped = HashMap::with_capacity(lod_steps.skipped.len());
+
+    for (entity, pos, collider, simplified) in &bodies {
+        let dist_sq = anchors
+            .iter()
+            .map(|anchor| anchor.0.distance_squared(pos.0))
+            .fold(f32::INFINITY, f32::min);
+
+        if let Some(distance) = lod.simplified_distance
+            && collider.is_some_and(|collider| matches!(collider.ctype, ColliderType::Dynamic(_)))
+        {
+            let far = dist_sq > distance * distance;
+            if far && !simplified {
+                commands.entity(entity).insert(SimplifiedPhysics);
+            } else if !far && simplified {
+                commands.entity(entity).remove::<SimplifiedPhysics>();
+            }
+        }
+
+        let pending = lod_steps.skipped.get(&entity).copied().unwrap_or(0) + 1;
+
+        let interval = if lod
+            .paused_distance
+            .is_some_and(|distance| dist_sq > distance * distance)
+        {
+            None
+        } else if dist_sq > lod.reduced_distance * lod.reduced_distance {
+            Some(lod.reduced_interval.max(1))
+        } else {
+            Some(1)
+        };
+
+        match interval {
+            Some(interval) if pending >= interval => {
+                if pending != 1 {
+                    lod_steps.steps.insert(entity, pending);
+                }
+            },
+            Some(_) => {
+                lod_steps.steps.insert(entity, 0);
+                skipped.insert(entity, pending);
+            },
+            None => {
+                lod_steps.steps.insert(entity, 0);
+                skipped.insert(entity, pending.min(lod.max_catch_up_ticks));
+            },
+        }
+    }
+
+    lod_steps.skipped = skipped;
+}
+
+/// Stops integrating bodies another peer has `Authority` over, their `Position` is replicated.
+pub(crate) fn skip_remote_bodies(
+    mut lod_steps: ResMut<LodSteps>,
+    local_client: Option<Res<LocalClient>>,
+    bodies: Query<(Entity, Option<&Authority>), With<Movement>>,
+) {
+    let local_client = local_client.map(|local_client| local_client.id);
+
+    for (entity, authority) in &bodies {
+        if !authority
+            .copied()
+            .unwrap_or_default()
+            .is_local(local_client)
+        {
+            lod_steps.skip(entity);
+        }
+    }
+}
+
+/// Pushes a body that leaves `SimplifiedPhysics` out of the static colliders it moved into while
+/// it was not colliding, so it does not get stuck in a wall.
+pub(crate) fn snap_out_of_statics(
+    remove: On<Remove, SimplifiedPhysics>,
+    mut bodies: Query<(&mut Position, &Collider, Option<&CollisionLayers>)>,
+    spatial_grid: Res<SpatialHashGrid>,
+    config: Res<PhysicsConfig>,
+) {
+    let Ok((pos, collider, layers)) = bodies.get(remove.entity) else {
+        return;
+    };
+
+    if !matches!(collider.ctype, ColliderType::Dynamic(_)) {
+        return;
+    }
+
+    let (mut pos, collider) = (pos.0, *collider);
+    let layers = layers.copied().unwrap_or_default();
+
+    // each push can move the body into a neighbouring static
+    for _ in 0..config.solver_iterations.max(1) {
+        let half_size = collider.size * 0.5;
+        let mut correction = Vec2::ZERO;
+
+        for other in spatial_grid.entities_in_rect(pos - half_size, pos + half_size) {
+            let Ok((other_pos, other_collider, other_layers)) = bodies.get(other) else {
+                continue;
+            };
+
+            if other == remove.entity
+                || !matches!(other_collider.ctype, ColliderType::Static)
+                || !layers.interacts_with(&other_layers.copied().unwrap_or_default())
+            {
+                continue;
+            }
+
+            let other_pos = pos + config.topology.delta(pos, other_pos.0);
+            if let Some(mtv) = collider.penetration(pos, other_collider, other_pos)
+                && mtv.length_squared() > correction.length_squared()
+            {
+                correction = -mtv;
+            }
+        }
+
+        if correction == Vec2::ZERO {
+            break;
+        }
+        pos += correction;
+    }
+
+    if let Ok((mut body_pos, ..)) = bodies.get_mut(remove.entity) {
+        body_pos.0 = pos;
+    }
+}
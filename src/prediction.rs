@@ -0,0 +1,96 @@
+use crate::*;
+use bevy_ecs::{entity::EntityHashMap, prelude::*};
+use bevy_math::prelude::*;
+use bevy_time::prelude::*;
+
+/// Positions of the predicted bodies at the end of the last physics tick.
+#[derive(Resource, Default)]
+pub(crate) struct PredictedPositions(EntityHashMap<Vec2>);
+
+/// Turns changes of `Position` since the last physics tick into a `PredictionError`, and shrinks
+/// the errors of earlier corrections.
+pub(crate) fn smooth_corrections(
+    mut commands: Commands,
+    mut bodies: Query<(
+        Entity,
+        &Position,
+        Option<&Authority>,
+        Option<&mut PredictionError>,
+    )>,
+    predicted: Res<PredictedPositions>,
+    local_client: Res<LocalClient>,
+    config: Res<PhysicsConfig>,
+    time: Res<Time>,
+) {
+    let window = local_client.correction_window;
+    let delta = time.delta_secs();
+
+    for (entity, pos, authority, error) in &mut bodies {
+        if !authority
+            .copied()
+            .unwrap_or_default()
+            .is_local(Some(local_client.id))
+        {
+            continue;
+        }
+
+        // shrink linearly, so the error is gone once no time remains
+        let mut offset = Vec2::ZERO;
+        if let Some(error) = &error {
+            let remaining = error.remaining - delta;
+            if remaining > 0.0 {
+                offset = error.offset * remaining / error.remaining;
+            }
+        }
+
+        let correction = predicted
+            .0
+            .get(&entity)
+            .map_or(Vec2::ZERO, |before| config.topology.delta(pos.0, *before));
+
+        let new_error = if correction != Vec2::ZERO && window > 0.0 {
+            Some(PredictionError {
+                offset: offset + correction,
+                remaining: window,
+            })
+        } else if offset != Vec2::ZERO {
+            error.as_ref().map(|error| PredictionError {
+                offset,
+                remaining: error.remaining - delta,
+            })
+        } else {
+            None
+        };
+
+        match (new_error, error) {
+            (Some(new_error), Some(mut error)) => *error = new_error,
+            (Some(new_error), None) => {
+                commands.entity(entity).insert(new_error);
+            },
+            (None, Some(_)) => {
+                commands.entity(entity).remove::<PredictionError>();
+            },
+            (None, None) => {},
+        }
+    }
+}
+
+/// Remembers where the predicted bodies ended up, to notice corrections before the next tick.
+pub(crate) fn record_predictions(
+    mut predicted: ResMut<PredictedPositions>,
+    bodies: Query<(Entity, &Position, Option<&Authority>)>,
+    local_client: Res<LocalClient>,
+) {
+    predicted.0.clear();
+    predicted.0.extend(
+        bodies
+            .iter()
+            .filter(|(_, _, authority)| {
+                authority
+                    .copied()
+                    .unwrap_or_default()
+                    .is_local(Some(local_client.id))
+            })
+            .map(|(entity, pos, _)| (entity, pos.0)),
+    );
+}
@@ -0,0 +1,106 @@
+use crate::*;
+use bevy_ecs::{component::Mutable, prelude::*};
+use bevy_math::prelude::*;
+use bevy_reflect::prelude::*;
+
+/// Adds or updates the force `id` of an entity's `Movement`, like `Movement::apply_force`.
+pub fn apply_force_by_name(
+    world: &mut World,
+    entity: Entity,
+    id: &str,
+    force: Vec2,
+) -> Result<(), ScriptingError> {
+    get_mut::<Movement>(world, entity)?.apply_force(PartialForce {
+        id: id.to_owned(),
+        force: Some(force),
+        ..Default::default()
+    });
+
+    Ok(())
+}
+
+/// Applies a `PartialForce` built from a dynamic value, e.g. a `DynamicStruct` filled by a script.
+///
+/// Missing fields are left at their default.
+pub fn apply_reflected_force(
+    world: &mut World,
+    entity: Entity,
+    partial: &dyn PartialReflect,
+) -> Result<(), ScriptingError> {
+    let partial = PartialForce::from_reflect(partial)
+        .ok_or_else(|| ScriptingError::InvalidValue("not a PartialForce".to_owned()))?;
+
+    get_mut::<Movement>(world, entity)?.apply_force(partial);
+    Ok(())
+}
+
+/// Sets a field of an entity's `Collider` by reflection path, e.g. `"size.x"` or `"radius"`.
+///
+/// The collider is only changed if the result passes `Collider::validate()`.
+pub fn set_collider_field(
+    world: &mut World,
+    entity: Entity,
+    path: &str,
+    value: &dyn PartialReflect,
+) -> Result<(), ScriptingError> {
+    let mut collider = get_mut::<Collider>(world, entity)?;
+    let mut updated = *collider;
+
+    updated
+        .reflect_path_mut(path)
+        .map_err(|err| ScriptingError::InvalidPath(err.to_string()))?
+        .try_apply(value)
+        .map_err(|err| ScriptingError::InvalidValue(err.to_string()))?;
+    updated
+        .validate()
+        .map_err(ScriptingError::InvalidCollider)?;
+
+    *collider = updated;
+    Ok(())
+}
+
+fn get_mut<T: Component<Mutability = Mutable>>(
+    world: &mut World,
+    entity: Entity,
+) -> Result<Mut<'_, T>, ScriptingError> {
+    world
+        .get_entity_mut(entity)
+        .map_err(|_| ScriptingError::NoSuchEntity(entity))?
+        .into_mut::<T>()
+        .ok_or(ScriptingError::MissingComponent {
+            entity,
+            component: std::any::type_name::<T>(),
+        })
+}
+
+/// Reasons a scripting call can fail.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScriptingError {
+    NoSuchEntity(Entity),
+    MissingComponent {
+        entity: Entity,
+        component: &'static str,
+    },
+    /// The reflection path does not name a field
+    InvalidPath(String),
+    /// The value does not have the type of the field
+    InvalidValue(String),
+    /// The new value would make the collider invalid
+    InvalidCollider(ColliderError),
+}
+
+impl std::fmt::Display for ScriptingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoSuchEntity(entity) => write!(f, "entity {entity} does not exist"),
+            Self::MissingComponent { entity, component } => {
+                write!(f, "entity {entity} has no {component}")
+            },
+            Self::InvalidPath(err) => write!(f, "invalid path: {err}"),
+            Self::InvalidValue(err) => write!(f, "invalid value: {err}"),
+            Self::InvalidCollider(err) => write!(f, "invalid collider: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ScriptingError {}
@@ -0,0 +1,168 @@
+use crate::*;
+use bevy_app::prelude::*;
+use bevy_ecs::{lifecycle::HookContext, prelude::*, world::DeferredWorld};
+use std::marker::PhantomData;
+
+type ColliderQuery = (
+    &'static Position,
+    &'static Collider,
+    Option<&'static UserData>,
+    Option<&'static CollisionLayers>,
+    Option<&'static RigidGroup>,
+);
+
+/// Makes a sensor only detect entities with the component `T`, e.g. a pickup that only cares
+/// about the player.
+///
+/// Filtered sensors are left out of `CollisionMessage` entirely. Their overlaps are checked
+/// separately against entities with `T` only, and sent as `SensorMessage<T>` once
+/// `SensorMessagePlugin::<T>` is added.
+///
+/// Removing the last `SensorFilter` of a sensor makes it report every overlap again.
+#[derive(Component)]
+#[require(FilteredSensor)]
+#[component(on_add = count_filter, on_remove = uncount_filter)]
+pub struct SensorFilter<T: Component>(PhantomData<T>);
+
+impl<T: Component> Default for SensorFilter<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+/// Required by every `SensorFilter` and removed with the last one, so the solver can skip
+/// filtered sensors without knowing `T`. Counts the filters of the entity.
+#[derive(Component, Default)]
+pub(crate) struct FilteredSensor(u32);
+
+fn count_filter(mut world: DeferredWorld, context: HookContext) {
+    if let Some(mut filtered) = world.get_mut::<FilteredSensor>(context.entity) {
+        filtered.0 += 1;
+    }
+}
+
+fn uncount_filter(mut world: DeferredWorld, context: HookContext) {
+    let Some(mut filtered) = world.get_mut::<FilteredSensor>(context.entity) else {
+        return;
+    };
+
+    filtered.0 = filtered.0.saturating_sub(1);
+    if filtered.0 == 0 {
+        world
+            .commands()
+            .entity(context.entity)
+            .try_remove::<FilteredSensor>();
+    }
+}
+
+/// Emitted every tick for each entity with `T` overlapping a sensor with `SensorFilter<T>`.
+///
+/// The contact data is seen from the sensor.
+#[derive(Message)]
+pub struct SensorMessage<T: Component> {
+    pub sensor: Entity,
+    pub entity: Entity,
+    pub contact: ContactData,
+    marker: PhantomData<T>,
+}
+
+impl<T: Component> Clone for SensorMessage<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sensor: self.sensor,
+            entity: self.entity,
+            contact: self.contact,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Component> std::fmt::Debug for SensorMessage<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SensorMessage")
+            .field("sensor", &self.sensor)
+            .field("entity", &self.entity)
+            .field("contact", &self.contact)
+            .finish()
+    }
+}
+
+/// Sends `SensorMessage<T>` for sensors with `SensorFilter<T>`. Add one per marker type.
+pub struct SensorMessagePlugin<T: Component>(PhantomData<T>);
+
+impl<T: Component> Default for SensorMessagePlugin<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: Component> Plugin for SensorMessagePlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.add_message::<SensorMessage<T>>();
+        app.add_systems(
+            FixedUpdate,
+            detect_filtered_overlaps::<T>
+                .after(solver::check_collisions_and_resolve)
+                .before(count_contacts)
+                .in_set(PhysicsSystems),
+        );
+    }
+}
+
+fn detect_filtered_overlaps<T: Component>(
+    mut messages: MessageWriter<SensorMessage<T>>,
+    sensors: Query<(Entity, ColliderQuery), With<SensorFilter<T>>>,
+    targets: Query<ColliderQuery, With<T>>,
+    spatial_grid: Res<SpatialHashGrid>,
+    config: Res<PhysicsConfig>,
+) {
+    let mut overlaps = Vec::new();
+    let mut neighbors = Vec::new();
+
+    for (sensor, (pos, collider, sensor_data, layers, group)) in &sensors {
+        if !spatial_grid.neighbors(sensor, &mut neighbors) {
+            continue;
+        }
+
+        let layers = layers.copied().unwrap_or_default();
+        let sensor_data = sensor_data.map_or(0, |user_data| user_data.0);
+
+        for &entity in &neighbors {
+            if entity == sensor {
+                continue;
+            }
+
+            let Ok((other_pos, other_collider, other_data, other_layers, other_group)) =
+                targets.get(entity)
+            else {
+                continue;
+            };
+
+            if !layers.interacts_with(&other_layers.copied().unwrap_or_default()) {
+                continue;
+            }
+
+            let other_pos = pos.0 + config.topology.delta(pos.0, other_pos.0);
+            let Some(mtv) = collider.penetration(pos.0, other_collider, other_pos) else {
+                continue;
+            };
+
+            let other_data = other_data.map_or(0, |user_data| user_data.0);
+            overlaps.push(SensorMessage {
+                sensor,
+                entity,
+                contact: ContactData::from_mtv(mtv)
+                    .with_user_data(sensor_data, other_data)
+                    .with_groups(
+                        group.map(|group| group.root),
+                        other_group.map(|group| group.root),
+                    ),
+                marker: PhantomData,
+            });
+        }
+    }
+
+    // same order as `CollisionMessage`
+    overlaps.sort_unstable_by_key(|message| (message.sensor, message.entity));
+    messages.write_batch(overlaps);
+}
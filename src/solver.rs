@@ -0,0 +1,1100 @@
+use crate::*;
+use bevy_ecs::{entity::EntityHashMap, prelude::*, query::QueryData, system::SystemParam};
+use bevy_math::{FloatOrd, prelude::*};
+use bevy_platform::collections::{HashMap, HashSet};
+use bevy_tasks::ComputeTaskPool;
+
+#[derive(QueryData)]
+#[query_data(mutable)]
+pub(crate) struct BodyQuery {
+    entity: Entity,
+    pos: &'static mut Position,
+    movement: &'static mut Movement,
+    collider: &'static Collider,
+    priority: Option<&'static PushPriority>,
+    platform: Option<&'static mut Platform>,
+    group: Option<&'static RigidGroup>,
+    friction: Option<&'static Friction>,
+    step_up: Option<&'static StepUp>,
+    wall_slide: Option<&'static WallSlide>,
+    user_data: Option<&'static UserData>,
+    layers: Option<&'static CollisionLayers>,
+    authority: Option<&'static Authority>,
+    filtered_sensor: Has<sensor::FilteredSensor>,
+    simplified: Has<SimplifiedPhysics>,
+    silent: Has<Silent>,
+}
+
+#[derive(Clone, Copy)]
+struct Body {
+    pos: Vec2,
+    speed: f32,
+    collider: Collider,
+    priority: PushPriority,
+    is_platform: bool,
+    friction: Option<Vec2>,
+    step_up: f32,
+    wall_slide: f32,
+    user_data: u128,
+    layers: CollisionLayers,
+    /// Sensor with a `SensorFilter`, whose overlaps are detected separately
+    filtered_sensor: bool,
+    /// Distance moved during this tick before collisions are resolved
+    displacement: Vec2,
+    /// Root of the rigid group this body belongs to
+    group: Option<Entity>,
+    /// Contacts involving this body do not send a `CollisionMessage`
+    silent: bool,
+    /// Simulated by another peer, it pushes the local bodies but is never moved here
+    remote: bool,
+}
+
+/// Rigid groups taking part in the current collision step
+#[derive(Default)]
+struct RigidGroups {
+    members: HashMap<Entity, Vec<Entity>>,
+    mass: HashMap<Entity, f32>,
+    /// Accumulated correction per root, used for roots without a collider
+    displacement: HashMap<Entity, Vec2>,
+}
+
+impl RigidGroups {
+    fn mass(&self, body: &Body, mass: f32) -> f32 {
+        body.group
+            .and_then(|root| self.mass.get(&root).copied())
+            .unwrap_or(mass)
+    }
+
+    /// Moves a body, or every member of its group if it belongs to one.
+    fn translate(&mut self, bodies: &mut HashMap<Entity, Body>, entity: Entity, delta: Vec2) {
+        let Some(body) = bodies.get_mut(&entity) else {
+            return;
+        };
+        let Some(root) = body.group else {
+            if !body.remote {
+                body.pos += delta;
+            }
+            return;
+        };
+
+        // members despawned during this tick are missing from the bodies
+        for member in self.members.get(&root).into_iter().flatten() {
+            let Some(body) = bodies.get_mut(member) else {
+                continue;
+            };
+            if !body.remote {
+                body.pos += delta;
+            }
+        }
+
+        *self.displacement.entry(root).or_default() += delta;
+    }
+}
+
+/// Combined correction of every static contact of a body.
+///
+/// Each axis keeps the largest push in either direction, so two tiles pushing the same way
+/// only move the body once.
+#[derive(Default)]
+struct StaticCorrection {
+    positive: Vec2,
+    negative: Vec2,
+}
+
+impl StaticCorrection {
+    fn add(&mut self, delta: Vec2) {
+        self.positive = self.positive.max(delta);
+        self.negative = self.negative.min(delta);
+    }
+
+    fn total(&self) -> Vec2 {
+        self.positive + self.negative
+    }
+}
+
+/// Largest part of an MTV's length across both axes for which it still counts as axis-aligned
+const AXIS_EPSILON: f32 = 1e-3;
+/// Minimum cosine between a diagonal MTV and a face touched on the last tick for the MTV to be
+/// treated as grazing the corner of that face
+const CORNER_STABILIZATION_COS: f32 = 0.5;
+/// Ticks a pair can be left over by `PhysicsConfig::max_pairs` before it is checked ahead of
+/// every pair that waited less
+const MAX_DEFERRED_TICKS: u32 = 4;
+/// Seconds between two `PairBudgetExceeded` while the budget stays exceeded
+const BUDGET_REPORT_INTERVAL: f32 = 1.0;
+
+/// Directions into the static colliders each dynamic body was pushed out of during the last tick.
+///
+/// Filled from the resolved contacts, so it also covers `Silent` bodies that send no messages.
+#[derive(Resource, Default)]
+pub(crate) struct StaticContacts(pub(crate) EntityHashMap<Vec<Vec2>>);
+
+impl StaticContacts {
+    fn record(&mut self, entity: Entity, mtv: Vec2) {
+        let normal = mtv.normalize_or_zero();
+        if normal != Vec2::ZERO {
+            self.0.entry(entity).or_default().push(normal);
+        }
+    }
+}
+
+/// Sensors overlapping another collider as `(sensor, other)`, found by the solver.
+///
+/// Pairs the solver did not check during a tick, deferred by `PhysicsConfig::max_pairs` or with a
+/// `SimplifiedPhysics` body, keep their last state.
+#[derive(Resource, Default)]
+pub(crate) struct SensorOverlaps(pub(crate) HashSet<(Entity, Entity)>);
+
+/// Directions into the static faces each dynamic body touched, carried over to the next tick.
+#[derive(Default)]
+pub(crate) struct FaceNormals(EntityHashMap<Vec<Vec2>>);
+
+impl FaceNormals {
+    /// Turns a diagonal MTV against a static collider into a push along the face the body was
+    /// touching, so bodies sliding along a flat wall of tiles are not launched sideways by the
+    /// rounded corners between them.
+    fn stabilize(&self, entity: Entity, mtv: Vec2) -> Vec2 {
+        let length = mtv.length();
+        if length == 0.0 || mtv.x.abs().min(mtv.y.abs()) <= length * AXIS_EPSILON {
+            return mtv;
+        }
+
+        let direction = mtv / length;
+        let face = self
+            .0
+            .get(&entity)
+            .into_iter()
+            .flatten()
+            .copied()
+            .max_by_key(|face| FloatOrd(face.dot(direction)));
+
+        match face {
+            Some(face) if face.dot(direction) >= CORNER_STABILIZATION_COS => face * mtv.dot(face),
+            _ => mtv,
+        }
+    }
+
+    /// Remembers an axis-aligned MTV as a face the body touches.
+    fn record(&mut self, entity: Entity, mtv: Vec2) {
+        let length = mtv.length();
+        if length == 0.0 || mtv.x.abs().min(mtv.y.abs()) > length * AXIS_EPSILON {
+            return;
+        }
+
+        let face = if mtv.x.abs() > mtv.y.abs() {
+            vec2(mtv.x.signum(), 0.0)
+        } else {
+            vec2(0.0, mtv.y.signum())
+        };
+
+        let faces = self.0.entry(entity).or_default();
+        if !faces.contains(&face) {
+            faces.push(face);
+        }
+    }
+}
+
+/// Pairs left over by `PhysicsConfig::max_pairs`, carried over to the next tick.
+#[derive(Default)]
+struct DeferredPairs {
+    /// Ticks each pair has been waiting for
+    ages: HashMap<(Entity, Entity), u32>,
+    /// Elapsed time of the last `PairBudgetExceeded`, `None` once the budget was kept
+    last_report: Option<f32>,
+}
+
+/// Applies `PhysicsConfig::max_pairs` to the candidate pairs of a tick.
+#[derive(SystemParam)]
+pub(crate) struct PairBudget<'w, 's> {
+    messages: MessageWriter<'w, PairBudgetExceeded>,
+    deferred: Local<'s, DeferredPairs>,
+    time: Res<'w, Time>,
+}
+
+impl PairBudget<'_, '_> {
+    /// Keeps the `max_pairs` candidates that matter most and defers the rest to a later tick.
+    fn limit(
+        &mut self,
+        candidates: &mut Vec<(Entity, Entity)>,
+        bodies: &HashMap<Entity, Body>,
+        anchors: &[Vec2],
+        max_pairs: Option<usize>,
+    ) {
+        let Some(max_pairs) = max_pairs.filter(|max| candidates.len() > *max) else {
+            self.deferred.ages.clear();
+            self.deferred.last_report = None;
+            return;
+        };
+
+        prioritize_pairs(candidates, bodies, anchors, &self.deferred.ages);
+
+        // reported when the budget is first exceeded, then at most once per interval
+        let now = self.time.elapsed_secs();
+        if self
+            .deferred
+            .last_report
+            .is_none_or(|last| now - last >= BUDGET_REPORT_INTERVAL)
+        {
+            self.deferred.last_report = Some(now);
+
+            #[cfg(feature = "log")]
+            bevy_log::warn!(
+                "{} broadphase pairs exceed the budget of {}, deferring the rest",
+                candidates.len(),
+                max_pairs
+            );
+
+            self.messages.write(PairBudgetExceeded {
+                pairs: candidates.len(),
+                deferred: candidates.len() - max_pairs,
+            });
+        }
+
+        let ages = candidates[max_pairs..]
+            .iter()
+            .map(|pair| (*pair, self.deferred.ages.get(pair).map_or(1, |age| age + 1)))
+            .collect();
+        self.deferred.ages = ages;
+        candidates.truncate(max_pairs);
+    }
+
+    /// Returns true if the pair was left over during this tick, in either order.
+    fn is_deferred(&self, (entity_a, entity_b): (Entity, Entity)) -> bool {
+        self.deferred.ages.contains_key(&(entity_a, entity_b))
+            || self.deferred.ages.contains_key(&(entity_b, entity_a))
+    }
+}
+
+/// Directions in which bodies are held in place by what they rest against, so a body pushed into
+/// them gives way instead of the push being split.
+///
+/// Keyed by the group root for members of a rigid group.
+#[derive(Default)]
+struct Blocked(HashMap<Entity, Vec<Vec2>>);
+
+impl Blocked {
+    /// Records that something pushes the body along `normal` and would stop it moving against it.
+    fn add(&mut self, body: &Body, entity: Entity, normal: Vec2) {
+        let normal = normal.normalize_or_zero();
+        if normal != Vec2::ZERO {
+            self.0
+                .entry(body.group.unwrap_or(entity))
+                .or_default()
+                .push(normal);
+        }
+    }
+
+    /// Returns true if moving the body along `push` would move it into something that stops it.
+    fn against(&self, body: &Body, entity: Entity, push: Vec2) -> bool {
+        self.0
+            .get(&body.group.unwrap_or(entity))
+            .is_some_and(|normals| normals.iter().any(|normal| normal.dot(push) < -1e-3))
+    }
+}
+
+/// Shortens the MTV by the allowed penetration, so resting contacts are not pushed apart
+/// and pulled back together every tick.
+fn beyond_slop(mtv: Vec2, slop: f32) -> Vec2 {
+    let penetration = mtv.length();
+    if penetration <= slop {
+        return Vec2::ZERO;
+    }
+
+    mtv * ((penetration - slop) / penetration)
+}
+
+/// Returns the fraction of the tick at which two bodies first ran into each other, for pairs that
+/// moved so far relative to each other that the overlap at the end of the tick is unreliable.
+///
+/// Checks `samples` positions evenly spread over the tick, then narrows down the first hit.
+/// Bodies that only graze each other, overlapping less than twice `slop` across their motion like
+/// a body running over a tiled floor, are left to the regular contact at the end of the tick.
+fn earliest_hit(body_a: &Body, body_b: &Body, samples: u32, slop: f32) -> Option<f32> {
+    let relative = body_a.displacement - body_b.displacement;
+    let extents = (body_a.collider.size + body_b.collider.size) * 0.5;
+    if samples < 2 || relative.abs().cmple(extents).all() {
+        return None;
+    }
+
+    let hit_at = |t: f32| {
+        let back = 1.0 - t;
+        let pos_a = body_a.pos - body_a.displacement * back;
+        let pos_b = body_b.pos - body_b.displacement * back;
+        let mtv = body_a
+            .collider
+            .penetration(pos_a, &body_b.collider, pos_b)?;
+
+        // the mtv points from a to b, so a moves into b if it points along the relative motion
+        let overlap = extents - (pos_b - pos_a).abs();
+        Some(mtv.dot(relative) > 0.0 && overlap.max_element() > slop * 2.0)
+    };
+
+    // already touching, e.g. resting against a wall it keeps running into
+    if let Some(hit) = hit_at(0.0) {
+        return hit.then_some(0.0);
+    }
+
+    let samples = samples as f32;
+    let first = (1..=samples as u32).find(|&i| hit_at(i as f32 / samples).is_some())?;
+
+    // narrow down between the last sample without overlap and the first one with
+    let mut before = (first - 1) as f32 / samples;
+    let mut after = first as f32 / samples;
+    for _ in 0..4 {
+        let mid = (before + after) * 0.5;
+        if hit_at(mid).is_some() {
+            after = mid;
+        } else {
+            before = mid;
+        }
+    }
+
+    (hit_at(after)? && after < 1.0).then_some(after)
+}
+
+/// Center of the overlap of the bounding boxes of two bodies.
+fn contact_point(body_a: &Body, body_b: &Body) -> Vec2 {
+    Rect::from_center_size(body_a.pos, body_a.collider.size)
+        .intersect(Rect::from_center_size(body_b.pos, body_b.collider.size))
+        .center()
+}
+
+/// Returns a copy of `body` moved to its image closest to `other` on a torus, so pairs across the
+/// edges of the world are resolved like any other pair.
+fn nearest_image(topology: &WorldTopology, other: &Body, mut body: Body) -> Body {
+    body.pos = other.pos + topology.delta(other.pos, body.pos);
+    body
+}
+
+/// Returns the bodies of a contact, `b` at its image closest to `a`.
+fn pair(
+    topology: &WorldTopology,
+    bodies: &HashMap<Entity, Body>,
+    entity_a: Entity,
+    entity_b: Entity,
+) -> (Body, Body) {
+    let body_a = bodies[&entity_a];
+    (body_a, nearest_image(topology, &body_a, bodies[&entity_b]))
+}
+
+/// Returns the bodies of a contact at the time they first touched, `b` at its image closest to `a`.
+///
+/// If the contact was only found by sampling, the dynamic bodies are moved back to where they hit
+/// so they are stopped there. Sensor contacts are only evaluated there, without moving anything.
+fn rewind_to_hit(
+    groups: &mut RigidGroups,
+    bodies: &mut HashMap<Entity, Body>,
+    hits: &HashMap<(Entity, Entity), f32>,
+    topology: &WorldTopology,
+    entity_a: Entity,
+    entity_b: Entity,
+) -> (Body, Body) {
+    let Some(&t) = hits.get(&(entity_a, entity_b)) else {
+        return pair(topology, bodies, entity_a, entity_b);
+    };
+
+    let back = 1.0 - t;
+    let blocking = [entity_a, entity_b]
+        .iter()
+        .all(|entity| !matches!(bodies[entity].collider.ctype, ColliderType::Sensor));
+
+    if !blocking {
+        let (mut body_a, mut body_b) = pair(topology, bodies, entity_a, entity_b);
+        body_a.pos -= body_a.displacement * back;
+        body_b.pos -= body_b.displacement * back;
+        return (body_a, body_b);
+    }
+
+    for entity in [entity_a, entity_b] {
+        let body = bodies[&entity];
+        if matches!(body.collider.ctype, ColliderType::Dynamic(_)) {
+            groups.translate(bodies, entity, -body.displacement * back);
+        }
+    }
+
+    pair(topology, bodies, entity_a, entity_b)
+}
+
+/// Returns how a dynamic body is moved out of a static one, and whether it steps up on top of it
+/// instead of being blocked.
+fn static_correction(body_a: &Body, body_b: &Body, mtv: Vec2, slop: f32) -> (Vec2, bool) {
+    // walking into a low enough static lifts a on top of it instead of blocking
+    let lift = (body_b.pos.y + body_b.collider.size.y * 0.5)
+        - (body_a.pos.y - body_a.collider.size.y * 0.5);
+    if mtv.x.abs() > mtv.y.abs() && lift > 0.0 && lift <= body_a.step_up {
+        (Vec2::new(0.0, lift), true)
+    } else {
+        (-beyond_slop(mtv, slop), false)
+    }
+}
+
+/// Returns how far a body with `WallSlide` moves along the walls that pushed it back by
+/// `correction`, making up for the part of its displacement the walls removed.
+fn wall_slide(body: &Body, correction: Vec2) -> Vec2 {
+    let normal = correction.normalize_or_zero();
+    let removed = -body.displacement.dot(normal);
+    if body.wall_slide <= 0.0 || removed <= 0.0 {
+        return Vec2::ZERO;
+    }
+
+    // moving straight into the wall has no direction to slide in
+    let tangent = body.displacement - normal * body.displacement.dot(normal);
+    let along = tangent.length();
+    if along <= AXIS_EPSILON {
+        return Vec2::ZERO;
+    }
+
+    // never faster along the wall than the body was moving in the first place
+    let slide = (removed * body.wall_slide).min(body.displacement.length() - along);
+    tangent / along * slide
+}
+
+/// Keeps the static contact that opposes the displacement of a body the most.
+fn record_blocker(
+    blockers: &mut HashMap<Entity, (Entity, f32)>,
+    entity: Entity,
+    body: &Body,
+    blocker: Entity,
+    mtv: Vec2,
+) {
+    // the mtv points into the blocker, so it opposes motion going the same way
+    let opposed = mtv.normalize_or_zero().dot(body.displacement);
+    if opposed > 0.0
+        && blockers
+            .get(&entity)
+            .is_none_or(|(_, most)| opposed > *most)
+    {
+        blockers.insert(entity, (blocker, opposed));
+    }
+}
+
+/// Pushes two overlapping dynamic bodies apart and returns true if either moved.
+///
+/// A body pushed towards something that blocks it does not move, the other one takes the whole
+/// correction and is blocked in turn, so the push travels back along a row of boxes.
+/// Otherwise the correction is split based on their priorities, then their masses.
+fn push_apart(
+    groups: &mut RigidGroups,
+    bodies: &mut HashMap<Entity, Body>,
+    blocked: &mut Blocked,
+    (entity_a, entity_b): (Entity, Entity),
+    mtv: Vec2,
+    slop: f32,
+) -> bool {
+    let (body_a, body_b) = (bodies[&entity_a], bodies[&entity_b]);
+    let (ColliderType::Dynamic(mass_a), ColliderType::Dynamic(mass_b)) =
+        (body_a.collider.ctype, body_b.collider.ctype)
+    else {
+        return false;
+    };
+
+    let blocked_a = body_a.remote || blocked.against(&body_a, entity_a, -mtv);
+    let blocked_b = body_b.remote || blocked.against(&body_b, entity_b, mtv);
+
+    let (mass_share_a, mass_share_b) = match (blocked_a, blocked_b) {
+        _ if body_a.remote && body_b.remote => return false,
+        _ if body_a.remote => (1.0, 0.0),
+        _ if body_b.remote => (0.0, 1.0),
+        (false, true) => (0.0, 1.0),
+        (true, false) => (1.0, 0.0),
+        _ => match body_a.priority.cmp(&body_b.priority) {
+            std::cmp::Ordering::Less => (0.0, 1.0),
+            std::cmp::Ordering::Greater => (1.0, 0.0),
+            std::cmp::Ordering::Equal => {
+                let mass_a = groups.mass(&body_a, mass_a);
+                let mass_b = groups.mass(&body_b, mass_b);
+                let total_mass = mass_a + mass_b;
+                (mass_a / total_mass, mass_b / total_mass)
+            },
+        },
+    };
+
+    if blocked_b && !blocked_a {
+        blocked.add(&body_a, entity_a, -mtv);
+    } else if blocked_a && !blocked_b {
+        blocked.add(&body_b, entity_b, mtv);
+    }
+
+    let mtv = beyond_slop(mtv, slop);
+    groups.translate(bodies, entity_a, -mtv * mass_share_b);
+    groups.translate(bodies, entity_b, mtv * mass_share_a);
+
+    mtv != Vec2::ZERO
+}
+
+/// Sorts pairs so the ones that matter most come first: pairs that waited too long, oldest first,
+/// then pairs with a dynamic body, closest to a `SimulationAnchor` first. Pairs that are still
+/// tied, e.g. every pair without anchors, are ordered by entity, so the order is deterministic.
+fn prioritize_pairs(
+    pairs: &mut [(Entity, Entity)],
+    bodies: &HashMap<Entity, Body>,
+    anchors: &[Vec2],
+    ages: &HashMap<(Entity, Entity), u32>,
+) {
+    let priority = |&(a, b): &(Entity, Entity)| {
+        let dist_sq = [a, b]
+            .into_iter()
+            .filter(|entity| matches!(bodies[entity].collider.ctype, ColliderType::Dynamic(_)))
+            .map(|entity| {
+                anchors
+                    .iter()
+                    .map(|anchor| anchor.distance_squared(bodies[&entity].pos))
+                    .fold(
+                        if anchors.is_empty() {
+                            0.0
+                        } else {
+                            f32::INFINITY
+                        },
+                        f32::min,
+                    )
+            })
+            .reduce(f32::min);
+
+        // pairs without any dynamic body only produce messages
+        dist_sq.unwrap_or(f32::INFINITY)
+    };
+
+    pairs.sort_by_cached_key(|pair| {
+        // pairs below the limit all get the same age key and are ordered by distance instead
+        let age = ages.get(pair).copied().unwrap_or_default();
+        (
+            age < MAX_DEFERRED_TICKS,
+            std::cmp::Reverse(age.max(MAX_DEFERRED_TICKS)),
+            FloatOrd(priority(pair)),
+            *pair,
+        )
+    });
+}
+
+/// Returns the candidate pairs that touch, in the same order, with the fraction of the tick at which
+/// they first hit for pairs found by sampling.
+///
+/// With `PhysicsConfig::parallel` the pairs are split into chunks checked on the compute task pool.
+/// The results are merged in chunk order, so they are the same as on the serial path.
+fn narrow_phase(
+    candidates: &[(Entity, Entity)],
+    bodies: &HashMap<Entity, Body>,
+    config: &PhysicsConfig,
+) -> Vec<((Entity, Entity), Option<f32>)> {
+    let check = |&(entity_a, entity_b): &(Entity, Entity)| {
+        let (body_a, body_b) = pair(&config.topology, bodies, entity_a, entity_b);
+
+        if let Some(t) = earliest_hit(&body_a, &body_b, config.narrow_phase_samples, config.slop) {
+            Some(((entity_a, entity_b), Some(t)))
+        } else {
+            body_a
+                .collider
+                .penetration(body_a.pos, &body_b.collider, body_b.pos)
+                .map(|_| ((entity_a, entity_b), None))
+        }
+    };
+
+    let pool = ComputeTaskPool::try_get().filter(|pool| config.parallel && pool.thread_num() > 1);
+    let Some(pool) = pool else {
+        return candidates.iter().filter_map(check).collect();
+    };
+
+    // small chunks cost more to schedule than to check
+    let chunk_size = candidates.len().div_ceil(pool.thread_num()).max(256);
+    let check = &check;
+    pool.scope(|scope| {
+        for chunk in candidates.chunks(chunk_size) {
+            scope.spawn(async move { chunk.iter().filter_map(check).collect::<Vec<_>>() });
+        }
+    })
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+pub(crate) fn check_collisions_and_resolve(
+    mut messages: MessageWriter<CollisionMessage>,
+    mut anomalies: MessageWriter<PhysicsAnomaly>,
+    mut containments: MessageWriter<ContainedWithin>,
+    mut query: Query<BodyQuery>,
+    mut group_roots: Query<&mut Position, Without<Collider>>,
+    exceptions: Query<(Entity, &CollisionExceptions)>,
+    spatial_grid: Res<SpatialHashGrid>,
+    config: Res<PhysicsConfig>,
+    local_client: Option<Res<LocalClient>>,
+    lod_steps: Res<lod::LodSteps>,
+    mut budget: PairBudget,
+    mut blocked_messages: MessageWriter<MovementBlocked>,
+    mut face_normals: Local<FaceNormals>,
+    mut static_contact_normals: ResMut<StaticContacts>,
+    mut sensor_overlaps: ResMut<SensorOverlaps>,
+) {
+    #[cfg(feature = "metrics")]
+    let solve_start = std::time::Instant::now();
+    let local_client = local_client.map(|local_client| local_client.id);
+    let len = query.iter().len();
+    let ignored = exception::ignored_pairs(&exceptions);
+    let mut bodies = HashMap::with_capacity(len);
+    let mut platform_riders: HashMap<Entity, Vec<Entity>> = HashMap::new();
+    let mut groups = RigidGroups::default();
+    let mut contact_friction: HashMap<Entity, Vec2> = HashMap::new();
+    static_contact_normals.0.clear();
+    let mut simplified = HashSet::new();
+
+    for item in query.iter() {
+        if item.simplified {
+            simplified.insert(item.entity);
+            continue;
+        }
+
+        let group = item.group.map(|group| group.root);
+        if let Some(root) = group {
+            groups.members.entry(root).or_default().push(item.entity);
+        }
+
+        // another peer decides where remote bodies end up, they push like kinematic bodies here
+        let remote = !item
+            .authority
+            .copied()
+            .unwrap_or_default()
+            .is_local(local_client);
+
+        bodies.insert(
+            item.entity,
+            Body {
+                pos: item.pos.0,
+                speed: item.movement.velocity.length(),
+                displacement: item.movement.velocity,
+                collider: *item.collider,
+                priority: item.priority.copied().unwrap_or_default(),
+                is_platform: item.platform.is_some(),
+                friction: item.friction.map(|friction| friction.0),
+                step_up: item.step_up.map_or(0.0, |step_up| step_up.0),
+                wall_slide: item
+                    .wall_slide
+                    .map_or(0.0, |wall_slide| wall_slide.0.clamp(0.0, 1.0)),
+                user_data: item.user_data.map_or(0, |user_data| user_data.0),
+                layers: item.layers.copied().unwrap_or_default(),
+                filtered_sensor: item.filtered_sensor
+                    && matches!(item.collider.ctype, ColliderType::Sensor),
+                group,
+                silent: item.silent,
+                remote,
+            },
+        );
+    }
+
+    // a root with its own collider moves together with its members
+    for (&root, members) in &mut groups.members {
+        if let Some(body) = bodies.get_mut(&root) {
+            body.group = Some(root);
+            members.push(root);
+        }
+
+        let mass = members
+            .iter()
+            .filter_map(|member| match bodies[member].collider.ctype {
+                ColliderType::Dynamic(mass) => Some(mass),
+                _ => None,
+            })
+            .sum();
+        groups.mass.insert(root, mass);
+    }
+
+    let mut candidates = Vec::new();
+
+    for (entity_a, entity_b) in spatial_grid.potential_pairs() {
+        if ignored.contains(&(entity_a, entity_b)) {
+            continue;
+        }
+
+        let (Some(body_a), Some(body_b)) = (bodies.get(&entity_a), bodies.get(&entity_b)) else {
+            continue;
+        };
+
+        // members of a rigid group never push each other
+        if body_a.group.is_some() && body_a.group == body_b.group {
+            continue;
+        }
+
+        if body_a.filtered_sensor
+            || body_b.filtered_sensor
+            || !body_a.layers.interacts_with(&body_b.layers)
+        {
+            continue;
+        }
+
+        // a moving body always comes first, so it is the one pushed out of an active static
+        let a_static = matches!(body_a.collider.ctype, ColliderType::Static);
+        if a_static && !matches!(body_b.collider.ctype, ColliderType::Static) {
+            candidates.push((entity_b, entity_a));
+        } else {
+            candidates.push((entity_a, entity_b));
+        }
+    }
+
+    budget.limit(
+        &mut candidates,
+        &bodies,
+        lod_steps.anchors(),
+        config.max_pairs,
+    );
+
+    let mut contacts = Vec::with_capacity(candidates.len());
+    let mut hits = HashMap::new();
+
+    for (pair, hit) in narrow_phase(&candidates, &bodies, &config) {
+        contacts.push(pair);
+        if let Some(t) = hit {
+            hits.insert(pair, t);
+        }
+    }
+
+    let is_sensor = |entity: &Entity| matches!(bodies[entity].collider.ctype, ColliderType::Sensor);
+    let mut next_sensor_overlaps = HashSet::new();
+    for &(entity_a, entity_b) in &contacts {
+        // silent bodies never trigger sensors
+        if bodies[&entity_a].silent || bodies[&entity_b].silent {
+            continue;
+        }
+        if is_sensor(&entity_a) {
+            next_sensor_overlaps.insert((entity_a, entity_b));
+        }
+        if is_sensor(&entity_b) {
+            next_sensor_overlaps.insert((entity_b, entity_a));
+        }
+    }
+
+    // pairs that were not checked this tick are still overlapping as far as anyone knows
+    next_sensor_overlaps.extend(
+        sensor_overlaps
+            .0
+            .iter()
+            .copied()
+            .filter(|&(sensor, other)| {
+                simplified.contains(&sensor)
+                    || simplified.contains(&other)
+                    || budget.is_deferred((sensor, other))
+            }),
+    );
+    sensor_overlaps.0 = next_sensor_overlaps;
+
+    let touching: HashSet<(Entity, Entity)> = contacts.iter().copied().collect();
+
+    // every pair that could touch this tick, so pushes never reach a body of another island
+    let islands = island::build_islands(&candidates, |ent| {
+        bodies
+            .get(&ent)
+            .filter(|body| matches!(body.collider.ctype, ColliderType::Dynamic(_)))
+            .map(|body| body.group.unwrap_or(ent))
+    });
+
+    let mut collisions = Vec::with_capacity(contacts.len());
+    let mut blocked = Blocked::default();
+    // static collider opposing the motion of each dynamic body the most, with how much
+    let mut blockers: HashMap<Entity, (Entity, f32)> = HashMap::new();
+    let mut next_face_normals = FaceNormals::default();
+
+    // writes the messages of a contact and returns its MTV at the given positions
+    let mut resolve_contact = |entity_a: Entity, body_a: Body, entity_b: Entity, body_b: Body| {
+        // positions may have changed since detection by earlier contacts in the island
+        let mut mtv = body_a
+            .collider
+            .penetration(body_a.pos, &body_b.collider, body_b.pos)?;
+
+        if matches!(body_a.collider.ctype, ColliderType::Dynamic(_))
+            && matches!(body_b.collider.ctype, ColliderType::Static)
+        {
+            mtv = face_normals.stabilize(entity_a, mtv);
+        }
+
+        if !body_a.silent && !body_b.silent {
+            collisions.push(CollisionMessage(
+                entity_a,
+                entity_b,
+                ContactData::from_mtv(mtv)
+                    .with_user_data(body_a.user_data, body_b.user_data)
+                    .with_groups(body_a.group, body_b.group)
+                    .with_motion(
+                        config.topology.wrap(contact_point(&body_a, &body_b)),
+                        body_a.displacement,
+                        body_b.displacement,
+                    ),
+            ));
+        }
+
+        if body_b
+            .collider
+            .contains(body_b.pos, &body_a.collider, body_a.pos)
+        {
+            containments.write(ContainedWithin(entity_a, entity_b));
+        } else if body_a
+            .collider
+            .contains(body_a.pos, &body_b.collider, body_b.pos)
+        {
+            containments.write(ContainedWithin(entity_b, entity_a));
+        }
+
+        let penetration = mtv.length();
+        for (entity, body) in [(entity_a, body_a), (entity_b, body_b)] {
+            if matches!(body.collider.ctype, ColliderType::Dynamic(_))
+                && penetration > body.collider.size.min_element() * 0.5
+            {
+                PhysicsAnomaly {
+                    entity,
+                    kind: PhysicsAnomalyKind::DeepPenetration,
+                    penetration,
+                    speed: body.speed,
+                }
+                .report(&mut anomalies);
+            }
+        }
+
+        Some(mtv)
+    };
+
+    for island in islands {
+        // pairs that can block each other, checked again after the first resolution
+        let solid_pairs: Vec<(Entity, Entity)> = island
+            .iter()
+            .copied()
+            .filter(|(entity_a, entity_b)| {
+                matches!(bodies[entity_a].collider.ctype, ColliderType::Dynamic(_))
+                    && !matches!(bodies[entity_b].collider.ctype, ColliderType::Sensor)
+            })
+            .collect();
+
+        let (static_contacts, dynamic_contacts): (Vec<_>, Vec<_>) = island
+            .into_iter()
+            .filter(|pair| touching.contains(pair))
+            .partition(|(_, entity_b)| {
+                matches!(bodies[entity_b].collider.ctype, ColliderType::Static)
+            });
+
+        // all static contacts of a body are measured from the same position and merged into a
+        // single correction, so touching several tiles at once does not overshoot
+        let mut corrections: HashMap<Entity, (Entity, StaticCorrection)> = HashMap::new();
+
+        for (entity_a, entity_b) in static_contacts {
+            let (body_a, body_b) = rewind_to_hit(
+                &mut groups,
+                &mut bodies,
+                &hits,
+                &config.topology,
+                entity_a,
+                entity_b,
+            );
+            let Some(mtv) = resolve_contact(entity_a, body_a, entity_b, body_b) else {
+                continue;
+            };
+
+            let (body_a, body_b) = pair(&config.topology, &bodies, entity_a, entity_b);
+            if body_a.remote || !matches!(body_a.collider.ctype, ColliderType::Dynamic(_)) {
+                continue;
+            }
+
+            next_face_normals.record(entity_a, mtv);
+            static_contact_normals.record(entity_a, mtv);
+
+            let (correction, stepping) = static_correction(&body_a, &body_b, mtv, config.slop);
+            if !stepping {
+                blocked.add(&body_a, entity_a, -mtv);
+                record_blocker(&mut blockers, entity_a, &body_a, entity_b, mtv);
+            }
+
+            corrections
+                .entry(body_a.group.unwrap_or(entity_a))
+                .or_insert((entity_a, StaticCorrection::default()))
+                .1
+                .add(correction);
+
+            if let Some(friction) = body_b.friction {
+                let total = contact_friction.entry(entity_a).or_default();
+                *total = total.max(friction);
+            }
+
+            // a is standing on top of the platform when pushed upwards
+            if body_b.is_platform && mtv.y < 0.0 && mtv.y.abs() >= mtv.x.abs() {
+                platform_riders.entry(entity_b).or_default().push(entity_a);
+            }
+        }
+
+        for (entity, correction) in corrections.into_values() {
+            let slide = wall_slide(&bodies[&entity], correction.total());
+            groups.translate(&mut bodies, entity, correction.total() + slide);
+        }
+
+        for (entity_a, entity_b) in dynamic_contacts {
+            let (body_a, body_b) = rewind_to_hit(
+                &mut groups,
+                &mut bodies,
+                &hits,
+                &config.topology,
+                entity_a,
+                entity_b,
+            );
+            let Some(mtv) = resolve_contact(entity_a, body_a, entity_b, body_b) else {
+                continue;
+            };
+
+            push_apart(
+                &mut groups,
+                &mut bodies,
+                &mut blocked,
+                (entity_a, entity_b),
+                mtv,
+                config.slop,
+            );
+        }
+
+        // pushes can move bodies into others that were not touching yet, e.g. the last box of a
+        // row into a wall, so keep resolving at the new positions until nothing overlaps anymore
+        for _ in 0..config.solver_iterations {
+            let mut moved = false;
+            let mut corrections: HashMap<Entity, (Entity, StaticCorrection)> = HashMap::new();
+
+            for &(entity_a, entity_b) in &solid_pairs {
+                let (body_a, body_b) = pair(&config.topology, &bodies, entity_a, entity_b);
+                if body_a.remote || !matches!(body_b.collider.ctype, ColliderType::Static) {
+                    continue;
+                }
+
+                let Some(mtv) =
+                    body_a
+                        .collider
+                        .penetration(body_a.pos, &body_b.collider, body_b.pos)
+                else {
+                    continue;
+                };
+                let mtv = face_normals.stabilize(entity_a, mtv);
+                next_face_normals.record(entity_a, mtv);
+                static_contact_normals.record(entity_a, mtv);
+
+                let (correction, stepping) = static_correction(&body_a, &body_b, mtv, config.slop);
+                if !stepping {
+                    blocked.add(&body_a, entity_a, -mtv);
+                    record_blocker(&mut blockers, entity_a, &body_a, entity_b, mtv);
+                }
+
+                corrections
+                    .entry(body_a.group.unwrap_or(entity_a))
+                    .or_insert((entity_a, StaticCorrection::default()))
+                    .1
+                    .add(correction);
+            }
+
+            for (entity, correction) in corrections.into_values() {
+                moved |= correction.total() != Vec2::ZERO;
+                groups.translate(&mut bodies, entity, correction.total());
+            }
+
+            for &(entity_a, entity_b) in &solid_pairs {
+                let (body_a, body_b) = pair(&config.topology, &bodies, entity_a, entity_b);
+                if let Some(mtv) =
+                    body_a
+                        .collider
+                        .penetration(body_a.pos, &body_b.collider, body_b.pos)
+                {
+                    moved |= push_apart(
+                        &mut groups,
+                        &mut bodies,
+                        &mut blocked,
+                        (entity_a, entity_b),
+                        mtv,
+                        config.slop,
+                    );
+                }
+            }
+
+            if !moved {
+                break;
+            }
+        }
+    }
+
+    *face_normals = next_face_normals;
+
+    #[cfg(feature = "metrics")]
+    telemetry::record_solve(solve_start, candidates.len(), contacts.len());
+
+    // hash iteration order is not stable, so sort to keep logs and replays deterministic
+    collisions.sort_unstable_by_key(|CollisionMessage(a, b, _)| (*a, *b));
+    messages.write_batch(collisions);
+
+    for (root, displacement) in groups.displacement {
+        if let Ok(mut pos) = group_roots.get_mut(root) {
+            pos.0 += displacement;
+        }
+    }
+
+    for mut item in &mut query {
+        if let Some(body) = bodies.get(&item.entity)
+            && matches!(body.collider.ctype, ColliderType::Dynamic(_))
+            && !body.remote
+        {
+            if let Some(&(blocker, _)) = blockers.get(&item.entity) {
+                let intended = body.displacement;
+                let actual = intended + body.pos - item.pos.0;
+                if actual.dot(intended) < intended.length_squared() * 0.5 {
+                    blocked_messages.write(MovementBlocked {
+                        entity: item.entity,
+                        intended,
+                        actual,
+                        blocker,
+                    });
+                }
+            }
+
+            item.pos.0 = body.pos;
+
+            let friction = contact_friction
+                .get(&item.entity)
+                .copied()
+                .unwrap_or_default();
+            if item.movement.friction != friction {
+                item.movement.friction = friction;
+            }
+        }
+
+        if let Some(platform) = &mut item.platform {
+            platform.riders = platform_riders.remove(&item.entity).unwrap_or_default();
+        }
+    }
+}
+
+pub(crate) fn broadphase_only(config: Res<PhysicsConfig>) -> bool {
+    config.broadphase_only
+}
+
+/// Sends the broadphase pairs the solver would check, without resolving anything.
+pub(crate) fn send_potential_contacts(
+    mut potential_contacts: MessageWriter<PotentialContact>,
+    query: Query<(
+        &Collider,
+        Option<&RigidGroup>,
+        Has<ActiveStatic>,
+        Option<&CollisionLayers>,
+    )>,
+    exceptions: Query<(Entity, &CollisionExceptions)>,
+    spatial_grid: Res<SpatialHashGrid>,
+) {
+    let ignored = exception::ignored_pairs(&exceptions);
+
+    let pairs = spatial_grid.potential_pairs().filter(|pair| {
+        let (
+            Ok((collider_a, group_a, active_a, layers_a)),
+            Ok((collider_b, group_b, active_b, layers_b)),
+        ) = (query.get(pair.0), query.get(pair.1))
+        else {
+            return false;
+        };
+
+        let layers_a = layers_a.copied().unwrap_or_default();
+        if !layers_a.interacts_with(&layers_b.copied().unwrap_or_default()) {
+            return false;
+        }
+
+        let passive_a = matches!(collider_a.ctype, ColliderType::Static) && !active_a;
+        let passive_b = matches!(collider_b.ctype, ColliderType::Static) && !active_b;
+        let same_group = group_a.is_some() && group_a.map(|g| g.root) == group_b.map(|g| g.root);
+
+        !(passive_a && passive_b || same_group || ignored.contains(pair))
+    });
+
+    potential_contacts.write_batch(pairs.map(|(a, b)| PotentialContact(a, b)));
+}
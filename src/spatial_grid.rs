@@ -1,94 +1,404 @@
-use crate::*;
+use crate::{components::hash_table_bytes, *};
 use bevy_ecs::prelude::*;
 use bevy_math::prelude::*;
-use bevy_platform::collections::{HashMap, HashSet};
+use bevy_platform::{
+    collections::{HashMap, HashSet},
+    hash::FixedHasher,
+};
+use core::hash::BuildHasher;
 use tinyvec::TinyVec;
 
+/// Size of the storage of a `SpatialHashGrid`, see `SpatialHashGrid::memory_usage()`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GridMemoryUsage {
+    /// Cells with storage, including empty ones
+    pub cells: usize,
+    /// Cells with storage but without any entity
+    pub empty_cells: usize,
+    /// Entities in the grid
+    pub entities: usize,
+    /// Entries of every cell, an entity counts once for each cell it occupies
+    pub cell_entries: usize,
+    /// Estimated heap size in bytes, from the capacity of every collection
+    pub bytes: usize,
+}
+
+/// Broadphase grid that maps cells to the colliders touching them.
+///
+/// `S` is the hasher of every internal collection. The physics plugins use the default
+/// `FixedHasher`, which hashes the same in every process so replays stay deterministic. Other
+/// hashers can be plugged in for grids built outside the plugins, see `with_hasher()`.
 #[derive(Resource)]
-pub struct SpatialHashGrid {
+pub struct SpatialHashGrid<S = FixedHasher> {
     pub(crate) cell_size: f32,
-    pub(crate) grid_to_ent: HashMap<IVec2, HashSet<Entity>>,
-    pub(crate) ent_to_grid: HashMap<Entity, HashSet<IVec2>>,
+    pub(crate) grid_to_ent: HashMap<IVec2, HashSet<Entity, S>, S>,
+    /// Cells of static colliders that do not look for contacts themselves, kept apart so they are
+    /// never paired with each other
+    pub(crate) grid_to_static: HashMap<IVec2, HashSet<Entity, S>, S>,
+    pub(crate) ent_to_grid: HashMap<Entity, HashSet<IVec2, S>, S>,
+    pub(crate) static_ents: HashSet<Entity, S>,
+    /// Copied from `PhysicsConfig` every tick, colliders crossing the edge of a torus also occupy
+    /// the cells on the other side
+    pub(crate) topology: WorldTopology,
+    /// Result of `potential_pairs()` at the last `refresh_pairs()`
+    pub(crate) pair_cache: Vec<(Entity, Entity)>,
+    /// Entities whose cells changed since the last `refresh_pairs()`, only their pairs are
+    /// found again
+    pub(crate) dirty: HashSet<Entity, S>,
 }
 
-impl Default for SpatialHashGrid {
+impl<S: BuildHasher + Clone + Default> Default for SpatialHashGrid<S> {
     fn default() -> Self {
-        Self {
-            cell_size: Self::DEFAULT_CELL_SIZE,
-            grid_to_ent: Default::default(),
-            ent_to_grid: Default::default(),
-        }
+        Self::with_hasher(SpatialHashGrid::DEFAULT_CELL_SIZE, S::default())
     }
 }
 
 impl SpatialHashGrid {
     pub(crate) const DEFAULT_CELL_SIZE: f32 = 20.0;
+}
 
-    pub(crate) fn insert_or_update(&mut self, ent: Entity, pos: &Position, coll: &Collider) {
-        let cells = self.find_cells(pos, coll);
+impl<S: BuildHasher + Clone + Default> SpatialHashGrid<S> {
+    /// Creates an empty grid with cells of `cell_size` tiles, using `hasher` for every
+    /// collection.
+    pub fn with_hasher(cell_size: f32, hasher: S) -> Self {
+        Self {
+            cell_size,
+            grid_to_ent: HashMap::with_hasher(hasher.clone()),
+            grid_to_static: HashMap::with_hasher(hasher.clone()),
+            ent_to_grid: HashMap::with_hasher(hasher.clone()),
+            static_ents: HashSet::with_hasher(hasher.clone()),
+            topology: WorldTopology::default(),
+            pair_cache: Vec::new(),
+            dirty: HashSet::with_hasher(hasher),
+        }
+    }
+
+    /// Inserts the entity into every cell its collider touches.
+    ///
+    /// `displacement` is how far the entity moved this tick. The cells along the way are
+    /// occupied too, so pairs the body passed through are still found by the broadphase.
+    /// `ahead` is how far the bounds reach past the collider in the direction of motion, see
+    /// `SpeculativeMargin`. `passive_static` is true for static colliders without `ActiveStatic`.
+    ///
+    /// Returns the previous cells if they changed.
+    pub(crate) fn insert_or_update(
+        &mut self,
+        ent: Entity,
+        pos: &Position,
+        coll: &Collider,
+        displacement: Vec2,
+        ahead: Vec2,
+        passive_static: bool,
+    ) -> Option<HashSet<IVec2, S>> {
+        let cells = self.find_cells(pos, coll, displacement, ahead);
 
         let existing_cells = self.ent_to_grid.get(&ent).cloned().unwrap_or_default();
-        if existing_cells != cells {
-            for cell in &existing_cells {
-                if let Some(set) = self.grid_to_ent.get_mut(cell) {
-                    set.remove(&ent);
-                }
-            }
+        let was_static = self.static_ents.contains(&ent);
+        if existing_cells == cells && was_static == passive_static {
+            return None;
+        }
 
-            self.ent_to_grid.insert(ent, cells.clone());
-            for cell in cells {
-                self.grid_to_ent.entry(cell).or_default().insert(ent);
+        let old_grid = if was_static {
+            &mut self.grid_to_static
+        } else {
+            &mut self.grid_to_ent
+        };
+        for cell in &existing_cells {
+            if let Some(set) = old_grid.get_mut(cell) {
+                set.remove(&ent);
             }
         }
+
+        let new_grid = if passive_static {
+            self.static_ents.insert(ent);
+            &mut self.grid_to_static
+        } else {
+            self.static_ents.remove(&ent);
+            &mut self.grid_to_ent
+        };
+        for cell in &cells {
+            new_grid.entry(*cell).or_default().insert(ent);
+        }
+
+        let changed = existing_cells != cells;
+        self.dirty.insert(ent);
+        self.ent_to_grid.insert(ent, cells);
+        changed.then_some(existing_cells)
     }
 
     pub(crate) fn remove(&mut self, ent: Entity) {
+        self.dirty.insert(ent);
+
+        let grid = if self.static_ents.remove(&ent) {
+            &mut self.grid_to_static
+        } else {
+            &mut self.grid_to_ent
+        };
+
         if let Some(grid_set) = self.ent_to_grid.remove(&ent) {
-            for grid in grid_set {
-                if let Some(ent_set) = self.grid_to_ent.get_mut(&grid) {
+            for cell in grid_set {
+                if let Some(ent_set) = grid.get_mut(&cell) {
                     ent_set.remove(&ent);
                 }
             }
         }
     }
 
-    fn find_cells(&self, pos: &Position, coll: &Collider) -> HashSet<IVec2> {
-        let rect = Rect::from_center_size(pos.0, coll.size);
-        let min_cell = (rect.min / self.cell_size).floor().as_ivec2();
-        let max_cell = (rect.max / self.cell_size).floor().as_ivec2();
+    /// Both grids, static colliders last.
+    fn grids(&self) -> [&HashMap<IVec2, HashSet<Entity, S>, S>; 2] {
+        [&self.grid_to_ent, &self.grid_to_static]
+    }
+
+    fn find_cells(
+        &self,
+        pos: &Position,
+        coll: &Collider,
+        displacement: Vec2,
+        ahead: Vec2,
+    ) -> HashSet<IVec2, S> {
+        let rect = Rect::from_center_size(pos.0, coll.size)
+            .union(Rect::from_center_size(pos.0 - displacement, coll.size))
+            .union(Rect::from_center_size(pos.0 + ahead, coll.size));
 
         let mut cells: TinyVec<[IVec2; 4]> = TinyVec::new();
+        let mut add_cells = |rect: Rect| {
+            let min_cell = (rect.min / self.cell_size).floor().as_ivec2();
+            let max_cell = (rect.max / self.cell_size).floor().as_ivec2();
 
-        for x in min_cell.x..=max_cell.x {
-            for y in min_cell.y..=max_cell.y {
-                cells.push(IVec2::new(x, y));
+            for x in min_cell.x..=max_cell.x {
+                for y in min_cell.y..=max_cell.y {
+                    cells.push(IVec2::new(x, y));
+                }
             }
+        };
+
+        match self.topology {
+            WorldTopology::Plane => add_cells(rect),
+            WorldTopology::Torus { size } => {
+                let world = Rect::from_corners(Vec2::ZERO, size);
+                for x in -1..=1 {
+                    for y in -1..=1 {
+                        let offset = vec2(x as f32, y as f32) * size;
+                        let image = Rect::from_center_size(rect.center() + offset, rect.size());
+                        if !image.intersect(world).is_empty() {
+                            add_cells(image);
+                        }
+                    }
+                }
+            },
         }
 
         cells.into_iter().collect()
     }
 
-    pub(crate) fn iter(&self, ent: Entity) -> Option<HashSet<Entity>> {
-        match self.ent_to_grid.get(&ent) {
-            Some(grid_set) => {
-                let mut entities = Vec::new();
+    /// Returns every entity occupying a cell that overlaps the rectangle from `min` to `max`.
+    ///
+    /// Each entity is returned once. Since whole cells are checked, entities slightly outside
+    /// the rectangle may be included.
+    pub fn entities_in_rect(&self, min: Vec2, max: Vec2) -> impl Iterator<Item = Entity> {
+        let min_cell = (min / self.cell_size).floor().as_ivec2();
+        let max_cell = (max / self.cell_size).floor().as_ivec2();
+        let cell_count = (max_cell - min_cell + 1).max(IVec2::ZERO).as_uvec2();
+
+        let mut entities = HashSet::new();
 
-                for grid in grid_set {
-                    match self.grid_to_ent.get(grid) {
-                        Some(ent_set) => {
+        for grid in self.grids() {
+            // for huge rects it is cheaper to go through the occupied cells instead
+            if (cell_count.x as usize).saturating_mul(cell_count.y as usize) > grid.len() {
+                for (cell, ent_set) in grid {
+                    if cell.cmpge(min_cell).all() && cell.cmple(max_cell).all() {
+                        entities.extend(ent_set);
+                    }
+                }
+            } else {
+                for x in min_cell.x..=max_cell.x {
+                    for y in min_cell.y..=max_cell.y {
+                        if let Some(ent_set) = grid.get(&IVec2::new(x, y)) {
                             entities.extend(ent_set);
-                        },
+                        }
+                    }
+                }
+            }
+        }
+
+        entities.into_iter()
+    }
+
+    /// Returns how crowded the area within `radius` of `point` is, in entities per square tile.
+    ///
+    /// Counts the distinct entities in every cell touching the circle and divides by the area of
+    /// those cells, so it is as coarse as the grid itself. Useful to pick low-density spawn points.
+    pub fn density_in(&self, radius: f32, point: Vec2) -> f32 {
+        let min_cell = ((point - radius) / self.cell_size).floor().as_ivec2();
+        let max_cell = ((point + radius) / self.cell_size).floor().as_ivec2();
+
+        let mut entities: HashSet<Entity> = HashSet::new();
+        let mut cell_count = 0;
 
-                        None => {
-                            return None;
-                        },
+        for x in min_cell.x..=max_cell.x {
+            for y in min_cell.y..=max_cell.y {
+                let cell = IVec2::new(x, y);
+                let cell_min = cell.as_vec2() * self.cell_size;
+                let closest = point.clamp(cell_min, cell_min + self.cell_size);
+                if closest.distance_squared(point) > radius * radius {
+                    continue;
+                }
+
+                cell_count += 1;
+                for grid in self.grids() {
+                    if let Some(ent_set) = grid.get(&cell) {
+                        entities.extend(ent_set);
                     }
                 }
+            }
+        }
 
-                Some(entities.into_iter().collect())
-            },
+        if cell_count == 0 {
+            return 0.0;
+        }
 
-            None => None,
+        entities.len() as f32 / (cell_count as f32 * self.cell_size * self.cell_size)
+    }
+
+    /// Returns the number of entities in every occupied cell, e.g. to build an occupancy texture.
+    ///
+    /// A cell covers `cell * cell_size()` to `(cell + 1) * cell_size()`.
+    pub fn cell_occupancy(&self) -> impl Iterator<Item = (IVec2, usize)> {
+        let mut occupancy: HashMap<IVec2, usize> = HashMap::new();
+        for grid in self.grids() {
+            for (cell, ent_set) in grid {
+                if !ent_set.is_empty() {
+                    *occupancy.entry(*cell).or_default() += ent_set.len();
+                }
+            }
         }
+
+        occupancy.into_iter()
+    }
+
+    /// Returns every pair of entities sharing at least one cell, for a custom narrow phase.
+    ///
+    /// Each pair is returned once as `(smaller, larger)`, sorted so the order is deterministic.
+    /// Like every broadphase result the colliders of a pair do not necessarily overlap.
+    /// Pairs of two static colliders without `ActiveStatic` are never returned.
+    ///
+    /// The pairs are cached between physics ticks, only the pairs of entities whose cells changed
+    /// are found again, so scenes of mostly resting bodies stay cheap.
+    pub fn potential_pairs(&self) -> impl Iterator<Item = (Entity, Entity)> + '_ {
+        // the cache is borrowed as is between ticks, only changed cells need a new list
+        let updated = (!self.dirty.is_empty()).then(|| self.updated_pairs());
+        let cached = updated.is_none().then_some(&self.pair_cache);
+
+        cached
+            .into_iter()
+            .flatten()
+            .copied()
+            .chain(updated.into_iter().flatten())
+    }
+
+    /// Updates the cached pairs to the current cells, called once per tick after the grid is updated.
+    pub(crate) fn refresh_pairs(&mut self) {
+        if !self.dirty.is_empty() {
+            self.pair_cache = self.updated_pairs();
+            self.dirty.clear();
+        }
+    }
+
+    /// Returns the cached pairs with the pairs of every dirty entity found again.
+    fn updated_pairs(&self) -> Vec<(Entity, Entity)> {
+        // a pair only depends on the cells of its two entities, so pairs of clean entities hold
+        let mut pairs: Vec<(Entity, Entity)> = self
+            .pair_cache
+            .iter()
+            .filter(|(a, b)| !self.dirty.contains(a) && !self.dirty.contains(b))
+            .copied()
+            .collect();
+
+        for &ent in &self.dirty {
+            let Some(grid_set) = self.ent_to_grid.get(&ent) else {
+                continue;
+            };
+            let with_statics = !self.static_ents.contains(&ent);
+
+            for cell in grid_set {
+                let statics = self.grid_to_static.get(cell).filter(|_| with_statics);
+                for &other in self
+                    .grid_to_ent
+                    .get(cell)
+                    .into_iter()
+                    .chain(statics)
+                    .flatten()
+                {
+                    if other != ent {
+                        pairs.push((ent.min(other), ent.max(other)));
+                    }
+                }
+            }
+        }
+
+        pairs.sort_unstable();
+        pairs.dedup();
+        pairs
+    }
+
+    /// Reports how much storage the grid uses, e.g. to notice cells or entities that are never
+    /// removed on a long-running server.
+    pub fn memory_usage(&self) -> GridMemoryUsage {
+        let mut usage = GridMemoryUsage {
+            entities: self.ent_to_grid.len(),
+            ..Default::default()
+        };
+
+        for grid in self.grids() {
+            usage.bytes += hash_table_bytes::<IVec2, HashSet<Entity, S>>(grid.capacity());
+            for ent_set in grid.values() {
+                usage.cells += 1;
+                usage.empty_cells += ent_set.is_empty() as usize;
+                usage.cell_entries += ent_set.len();
+                usage.bytes += hash_table_bytes::<Entity, ()>(ent_set.capacity());
+            }
+        }
+
+        usage.bytes += hash_table_bytes::<Entity, HashSet<IVec2, S>>(self.ent_to_grid.capacity());
+        for cells in self.ent_to_grid.values() {
+            usage.bytes += hash_table_bytes::<IVec2, ()>(cells.capacity());
+        }
+        usage.bytes += hash_table_bytes::<Entity, ()>(self.static_ents.capacity());
+        usage.bytes += self.pair_cache.capacity() * size_of::<(Entity, Entity)>();
+        usage.bytes += hash_table_bytes::<Entity, ()>(self.dirty.capacity());
+
+        usage
+    }
+
+    /// Size of a grid cell in tiles.
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Fills `neighbors` with the entities sharing a cell with `ent`, including itself, sorted and
+    /// without duplicates. Returns false if `ent` is not in the grid.
+    ///
+    /// Static colliders only get the entities that are not static themselves. The buffer is
+    /// reused across calls, so the broadphase does not allocate a set for every entity.
+    pub(crate) fn neighbors(&self, ent: Entity, neighbors: &mut Vec<Entity>) -> bool {
+        neighbors.clear();
+
+        let Some(grid_set) = self.ent_to_grid.get(&ent) else {
+            return false;
+        };
+        let with_statics = !self.static_ents.contains(&ent);
+
+        for cell in grid_set {
+            if let Some(ent_set) = self.grid_to_ent.get(cell) {
+                neighbors.extend(ent_set);
+            }
+            if with_statics && let Some(ent_set) = self.grid_to_static.get(cell) {
+                neighbors.extend(ent_set);
+            }
+        }
+
+        neighbors.sort_unstable();
+        neighbors.dedup();
+        true
     }
 }
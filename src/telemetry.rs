@@ -0,0 +1,69 @@
+//! Physics health exported through the `metrics` crate, enabled with the `metrics` feature.
+//!
+//! Install any `metrics` recorder, e.g. a Prometheus exporter, to scrape these.
+
+use crate::*;
+use bevy_ecs::prelude::*;
+use metrics::{counter, gauge, histogram};
+use std::time::Instant;
+
+/// Counter of simulated physics ticks
+pub const TICKS: &str = "rrect_physics_ticks_total";
+/// Histogram of the duration of a whole physics tick, in seconds
+pub const TICK_SECONDS: &str = "rrect_physics_tick_seconds";
+/// Histogram of the duration of collision detection and resolution, in seconds
+pub const SOLVE_SECONDS: &str = "rrect_physics_solve_seconds";
+/// Histogram of broadphase pairs checked per tick
+pub const PAIRS: &str = "rrect_physics_pairs";
+/// Histogram of contacts found per tick
+pub const CONTACTS: &str = "rrect_physics_contacts";
+/// Gauge of entities with a `Collider`
+pub const BODIES: &str = "rrect_physics_bodies";
+/// Gauge of entities with a dynamic `Collider`
+pub const DYNAMIC_BODIES: &str = "rrect_physics_dynamic_bodies";
+/// Gauge of bodies that are not fully simulated, because of `SimplifiedPhysics` or because the
+/// simulation LOD skips them this tick
+pub const INACTIVE_BODIES: &str = "rrect_physics_inactive_bodies";
+
+#[derive(Resource, Default)]
+pub(crate) struct TickStartTime(Option<Instant>);
+
+pub(crate) fn start_tick(mut start: ResMut<TickStartTime>) {
+    start.0 = Some(Instant::now());
+}
+
+pub(crate) fn record_tick(
+    mut start: ResMut<TickStartTime>,
+    colliders: Query<(&Collider, Has<SimplifiedPhysics>)>,
+    lod_steps: Res<lod::LodSteps>,
+) {
+    counter!(TICKS).increment(1);
+
+    if let Some(start) = start.0.take() {
+        histogram!(TICK_SECONDS).record(start.elapsed().as_secs_f64());
+    }
+
+    let mut bodies = 0;
+    let mut dynamic = 0;
+    let mut simplified = 0;
+    for (collider, is_simplified) in &colliders {
+        bodies += 1;
+        if matches!(collider.ctype, ColliderType::Dynamic(_)) {
+            dynamic += 1;
+        }
+        if is_simplified {
+            simplified += 1;
+        }
+    }
+
+    gauge!(BODIES).set(bodies as f64);
+    gauge!(DYNAMIC_BODIES).set(dynamic as f64);
+    gauge!(INACTIVE_BODIES).set((simplified + lod_steps.skipped_count()) as f64);
+}
+
+/// Records the size and duration of one run of the solver.
+pub(crate) fn record_solve(start: Instant, pairs: usize, contacts: usize) {
+    histogram!(SOLVE_SECONDS).record(start.elapsed().as_secs_f64());
+    histogram!(PAIRS).record(pairs as f64);
+    histogram!(CONTACTS).record(contacts as f64);
+}
@@ -0,0 +1,191 @@
+//! Helpers to write deterministic physics tests, enabled with the `test-utils` feature.
+//!
+//! ```
+//! use bevy_math::prelude::*;
+//! use pvw_rrect_physics::{prelude::*, test_utils::*};
+//!
+//! let mut app = physics_test_app();
+//! let wall = app
+//!     .world_mut()
+//!     .spawn((Position(vec2(3.0, 0.0)), Collider::rect(Vec2::ONE, ColliderType::Static)))
+//!     .id();
+//! let body = app
+//!     .world_mut()
+//!     .spawn((Position(Vec2::ZERO), Collider::rect(Vec2::ONE, ColliderType::Dynamic(1.0))))
+//!     .id();
+//!
+//! app.world_mut()
+//!     .get_mut::<Movement>(body)
+//!     .unwrap()
+//!     .apply_force(PartialForce {
+//!         id: "walk".to_owned(),
+//!         force: Some(vec2(10.0, 0.0)),
+//!         active: Some(true),
+//!         ..Default::default()
+//!     });
+//!
+//! advance_ticks(&mut app, 64);
+//! assert_no_overlaps(&mut app);
+//! assert_position_near(&app, body, vec2(2.0, 0.0), 0.01);
+//! # let _ = wall;
+//! ```
+
+use crate::*;
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_math::prelude::*;
+use bevy_time::{TimePlugin, TimeUpdateStrategy, prelude::*};
+
+/// Creates an app with only the time and headless physics plugins, without any window or renderer.
+///
+/// Time only advances through `advance_ticks()`, so tests do not depend on the speed of the machine.
+pub fn physics_test_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((TimePlugin, PvwRRectPhysicsPluginHeadless::default()));
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(
+        Time::<Fixed>::default().timestep(),
+    ));
+    app
+}
+
+/// Updates the app until exactly `ticks` more physics ticks have run.
+///
+/// Every update advances the clock by one fixed timestep, so each update runs a single tick.
+pub fn advance_ticks(app: &mut App, ticks: u32) {
+    let time = app.world().resource::<Time<Fixed>>();
+    let timestep = time.timestep();
+    let target = time.elapsed() + timestep * ticks;
+
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(timestep));
+    while app.world().resource::<Time<Fixed>>().elapsed() < target {
+        app.update();
+    }
+}
+
+/// Panics if two solid colliders overlap by more than `PhysicsConfig::slop`.
+///
+/// Sensors, `SimplifiedPhysics` bodies, pairs of static colliders and members of the same
+/// `RigidGroup` are ignored.
+pub fn assert_no_overlaps(app: &mut App) {
+    let slop = app
+        .world()
+        .get_resource::<PhysicsConfig>()
+        .map_or(0.0, |config| config.slop);
+
+    let world = app.world_mut();
+    let bodies: Vec<(Entity, Vec2, Collider, Option<Entity>)> = world
+        .query_filtered::<(Entity, &Position, &Collider, Option<&RigidGroup>), Without<SimplifiedPhysics>>()
+        .iter(world)
+        .filter(|(_, _, collider, _)| !matches!(collider.ctype, ColliderType::Sensor))
+        .map(|(entity, pos, collider, group)| (entity, pos.0, *collider, group.map(|g| g.root)))
+        .collect();
+
+    for (i, (entity_a, pos_a, collider_a, group_a)) in bodies.iter().enumerate() {
+        for (entity_b, pos_b, collider_b, group_b) in &bodies[i + 1..] {
+            let both_static = matches!(collider_a.ctype, ColliderType::Static)
+                && matches!(collider_b.ctype, ColliderType::Static);
+            if both_static || group_a.is_some() && group_a == group_b {
+                continue;
+            }
+
+            if let Some(mtv) = collider_a.penetration(*pos_a, collider_b, *pos_b) {
+                // a little tolerance for float error in resting contacts
+                assert!(
+                    mtv.length() <= slop + 1e-4,
+                    "{entity_a} at {pos_a} overlaps {entity_b} at {pos_b} by {mtv}",
+                );
+            }
+        }
+    }
+}
+
+/// Panics if the `Position` of `entity` is further than `tolerance` from `expected`.
+pub fn assert_position_near(app: &App, entity: Entity, expected: Vec2, tolerance: f32) {
+    let pos = app
+        .world()
+        .get::<Position>(entity)
+        .unwrap_or_else(|| panic!("{entity} has no Position"));
+
+    assert!(
+        pos.0.distance(expected) <= tolerance,
+        "{entity} is at {}, expected {expected} within {tolerance}",
+        pos.0,
+    );
+}
+
+/// Panics if any `Position` or `Movement::velocity` is NaN or infinite.
+pub fn assert_finite_positions(app: &mut App) {
+    let world = app.world_mut();
+    for (entity, pos, movement) in world
+        .query::<(Entity, &Position, Option<&Movement>)>()
+        .iter(world)
+    {
+        assert!(pos.0.is_finite(), "{entity} is at {}", pos.0);
+        if let Some(movement) = movement {
+            assert!(
+                movement.velocity.is_finite(),
+                "{entity} moves by {}",
+                movement.velocity,
+            );
+        }
+    }
+}
+
+/// Panics if any body moved further in the last tick than `Movement::MAX_VELOCITY` allows.
+///
+/// Solver corrections are included, so this also catches bodies that get launched out of an
+/// overlap.
+pub fn assert_speeds_bounded(app: &mut App) {
+    let dt = app
+        .world()
+        .resource::<Time<Fixed>>()
+        .timestep()
+        .as_secs_f32();
+    // corrections may push a body a bit further than its own velocity
+    let max = Movement::MAX_VELOCITY * dt * 2.0;
+
+    let world = app.world_mut();
+    for (entity, displacement) in world.query::<(Entity, &LinearDisplacement)>().iter(world) {
+        assert!(
+            displacement.0.length() <= max,
+            "{entity} moved {} in one tick, at most {max} expected",
+            displacement.0,
+        );
+    }
+}
+
+/// Panics if the two maps of `SpatialHashGrid` disagree, or if it still holds despawned entities.
+pub fn assert_grid_consistent(app: &App) {
+    let world = app.world();
+    let grid = world.resource::<SpatialHashGrid>();
+
+    for (entity, cells) in &grid.ent_to_grid {
+        assert!(
+            world.get_entity(*entity).is_ok(),
+            "despawned {entity} is still in the grid",
+        );
+
+        let map = if grid.static_ents.contains(entity) {
+            &grid.grid_to_static
+        } else {
+            &grid.grid_to_ent
+        };
+        for cell in cells {
+            assert!(
+                map.get(cell).is_some_and(|ents| ents.contains(entity)),
+                "{entity} thinks it is in cell {cell}, but the cell does not contain it",
+            );
+        }
+    }
+
+    for (cell, ents) in grid.grid_to_ent.iter().chain(&grid.grid_to_static) {
+        for entity in ents {
+            assert!(
+                grid.ent_to_grid
+                    .get(entity)
+                    .is_some_and(|cells| cells.contains(cell)),
+                "cell {cell} contains {entity}, but it does not know about the cell",
+            );
+        }
+    }
+}
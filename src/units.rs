@@ -0,0 +1,120 @@
+//! Conversions between the units used by the crate.
+//!
+//! - `PhysicsPos`: tiles, the unit of `Position`, `Collider::size` and forces
+//! - `TilePos`: integer coordinates of a tile. Tile `(0, 0)` is centered on `PhysicsPos(0, 0)`,
+//!   like a 1x1 collider at `Position(Vec2::ZERO)`
+//! - `WorldPos`: pixels, the unit of `Transform` and `Camera::viewport_to_world_2d()`
+//!
+//! Pixels are converted with `TileSize`, e.g. to spawn a body under the cursor:
+//!
+//! ```
+//! use bevy_math::prelude::*;
+//! use pvw_rrect_physics::prelude::*;
+//!
+//! let tile_size = TileSize::new(40.0);
+//! let cursor = WorldPos(vec2(100.0, 60.0));
+//!
+//! let pos = tile_size.to_physics(cursor);
+//! assert_eq!(pos, PhysicsPos(vec2(2.5, 1.5)));
+//! assert_eq!(pos.tile(), TilePos(ivec2(3, 2)));
+//! assert_eq!(tile_size.to_world(pos), cursor);
+//!
+//! let _body = Position::from(pos);
+//! ```
+
+use crate::*;
+use bevy_ecs::prelude::*;
+use bevy_math::prelude::*;
+
+/// Size of a tile in pixels, used to convert between `WorldPos` and `PhysicsPos`.
+#[derive(Debug, Resource, Clone, Copy)]
+pub struct TileSize(f32, Vec2);
+
+impl Default for TileSize {
+    fn default() -> Self {
+        Self::new(8.0)
+    }
+}
+
+impl TileSize {
+    pub fn new(size: f32) -> Self {
+        Self(size, Vec2::splat(size))
+    }
+
+    pub fn size(&self) -> f32 {
+        self.0
+    }
+
+    pub fn vec(&self) -> Vec2 {
+        self.1
+    }
+
+    pub fn to_world(&self, pos: PhysicsPos) -> WorldPos {
+        WorldPos(pos.0 * self.0)
+    }
+
+    pub fn to_physics(&self, pos: WorldPos) -> PhysicsPos {
+        PhysicsPos(pos.0 / self.0)
+    }
+
+    /// Returns the tile containing a point in pixels.
+    pub fn tile_at(&self, pos: WorldPos) -> TilePos {
+        self.to_physics(pos).tile()
+    }
+}
+
+/// Position in tiles, the unit of `Position`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PhysicsPos(pub Vec2);
+
+impl PhysicsPos {
+    /// Returns the tile containing this position.
+    pub fn tile(&self) -> TilePos {
+        TilePos((self.0 + 0.5).floor().as_ivec2())
+    }
+}
+
+impl From<Position> for PhysicsPos {
+    fn from(pos: Position) -> Self {
+        Self(pos.0)
+    }
+}
+
+impl From<PhysicsPos> for Position {
+    fn from(pos: PhysicsPos) -> Self {
+        Self(pos.0)
+    }
+}
+
+impl From<TilePos> for PhysicsPos {
+    fn from(tile: TilePos) -> Self {
+        tile.center()
+    }
+}
+
+/// Integer coordinates of a tile.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TilePos(pub IVec2);
+
+impl TilePos {
+    /// Returns the center of the tile.
+    pub fn center(&self) -> PhysicsPos {
+        PhysicsPos(self.0.as_vec2())
+    }
+}
+
+/// Position in pixels, the unit of `Transform`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct WorldPos(pub Vec2);
+
+impl WorldPos {
+    /// Drops the z of a `Transform` translation.
+    pub fn from_translation(translation: Vec3) -> Self {
+        Self(translation.truncate())
+    }
+
+    /// Returns a `Transform` translation with the given z.
+    pub fn extend(&self, z: f32) -> Vec3 {
+        self.0.extend(z)
+    }
+}
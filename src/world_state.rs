@@ -0,0 +1,376 @@
+use crate::*;
+use bevy_ecs::{
+    entity::{EntityHashMap, EntityMapper, MapEntities},
+    prelude::*,
+    world::{EntityRef, EntityWorldMut},
+};
+use bevy_platform::hash::FixedHasher;
+use serde::{Deserialize, Serialize};
+
+/// Serializable snapshot of every physics entity in a world, e.g. to move a match between server
+/// processes.
+///
+/// Entity references like `WeldedTo::parent` are remapped when the state is restored.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PhysicsWorldState {
+    pub cell_size: f32,
+    pub config: PhysicsConfig,
+    pub bodies: Vec<BodyState>,
+}
+
+/// The physics components of a single entity.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct BodyState {
+    /// The entity in the world the state was extracted from
+    pub entity: Option<Entity>,
+    pub position: Option<Position>,
+    pub movement: Option<Movement>,
+    pub collider: Option<Collider>,
+    pub push_priority: Option<PushPriority>,
+    pub platform: Option<Platform>,
+    pub welded_to: Option<WeldedTo>,
+    pub rigid_group: Option<RigidGroup>,
+    pub distance_joint: Option<DistanceJoint>,
+    pub constant_force: Option<ConstantForce>,
+    pub seek_target: Option<SeekTarget>,
+    pub flee_point: Option<FleePoint>,
+    pub friction: Option<Friction>,
+    pub step_up: Option<StepUp>,
+    pub user_data: Option<UserData>,
+    pub sensor_group: Option<SensorGroup>,
+    pub track_contacts: Option<TrackContacts>,
+    pub destructible: Option<Destructible>,
+    pub simulation_anchor: Option<SimulationAnchor>,
+    pub active_static: Option<ActiveStatic>,
+    pub collision_layers: Option<CollisionLayers>,
+    pub authority: Option<Authority>,
+    pub density: Option<Density>,
+    pub contact_damage: Option<ContactDamage>,
+    pub linear_displacement: Option<LinearDisplacement>,
+    pub simplified_physics: Option<SimplifiedPhysics>,
+    pub sync_mode: Option<SyncMode>,
+    pub z_index_mode: Option<ZIndexMode>,
+    pub silent: Option<Silent>,
+    pub tether: Option<TetherConstraint>,
+    pub collider_tween: Option<ColliderTween>,
+    pub speculative_margin: Option<SpeculativeMargin>,
+    pub avoidance: Option<Avoidance>,
+    pub hitbox_kind: Option<HitboxKind>,
+    pub physics_group: Option<PhysicsGroup>,
+    pub wall_slide: Option<WallSlide>,
+    pub spring_follower: Option<SpringFollower>,
+    pub collision_exceptions: Option<CollisionExceptions>,
+    pub render_offset: Option<RenderOffset>,
+    pub no_interpolation: Option<NoInterpolation>,
+}
+
+impl PhysicsWorldState {
+    /// Copies the physics components of every entity that has any of them.
+    pub fn extract(world: &mut World) -> Self {
+        let cell_size = world
+            .get_resource::<SpatialHashGrid>()
+            .map_or(SpatialHashGrid::DEFAULT_CELL_SIZE, |grid| grid.cell_size);
+        let config = world
+            .get_resource::<PhysicsConfig>()
+            .cloned()
+            .unwrap_or_default();
+
+        let bodies = world
+            .query::<EntityRef>()
+            .iter(world)
+            .map(BodyState::extract)
+            .filter(|body| !body.is_empty())
+            .collect();
+
+        Self {
+            cell_size,
+            config,
+            bodies,
+        }
+    }
+
+    /// Spawns every body into `world` and returns which new entity each extracted entity became.
+    pub fn restore(self, world: &mut World) -> EntityHashMap<Entity> {
+        let mut entity_map = EntityHashMap::default();
+        self.restore_with(world, &mut entity_map);
+        entity_map
+    }
+
+    /// Like `restore()`, but extracted entities that are already in `entity_map` are inserted into
+    /// the entity they map to instead of a new one, e.g. entities replicated by the network layer.
+    ///
+    /// Newly spawned entities are added to `entity_map`.
+    pub fn restore_with(self, world: &mut World, entity_map: &mut EntityHashMap<Entity>) {
+        // the grid is filled again as colliders are inserted and by the next physics tick
+        world.insert_resource(SpatialHashGrid {
+            topology: self.config.topology,
+            ..SpatialHashGrid::with_hasher(self.cell_size, FixedHasher)
+        });
+        world.insert_resource(self.config);
+
+        let targets: Vec<Entity> = self
+            .bodies
+            .iter()
+            .map(|body| match body.entity {
+                Some(entity) => *entity_map
+                    .entry(entity)
+                    .or_insert_with(|| world.spawn_empty().id()),
+                None => world.spawn_empty().id(),
+            })
+            .collect();
+
+        for (mut body, target) in self.bodies.into_iter().zip(targets) {
+            body.map_entities(entity_map);
+            body.insert_into(world.entity_mut(target));
+        }
+    }
+}
+
+impl BodyState {
+    pub fn extract(entity: EntityRef) -> Self {
+        Self {
+            entity: Some(entity.id()),
+            position: entity.get().copied(),
+            movement: entity.get().cloned(),
+            collider: entity.get().copied(),
+            push_priority: entity.get().copied(),
+            platform: entity.get().cloned(),
+            welded_to: entity.get().copied(),
+            rigid_group: entity.get().copied(),
+            distance_joint: entity.get().copied(),
+            constant_force: entity.get().copied(),
+            seek_target: entity.get().copied(),
+            flee_point: entity.get().copied(),
+            friction: entity.get().copied(),
+            step_up: entity.get().copied(),
+            user_data: entity.get().copied(),
+            sensor_group: entity.get().cloned(),
+            track_contacts: entity.get().copied(),
+            destructible: entity.get().copied(),
+            simulation_anchor: entity.get().copied(),
+            active_static: entity.get().copied(),
+            collision_layers: entity.get().copied(),
+            authority: entity.get().copied(),
+            density: entity.get().copied(),
+            contact_damage: entity.get().copied(),
+            linear_displacement: entity.get().copied(),
+            simplified_physics: entity.get().copied(),
+            sync_mode: entity.get().copied(),
+            z_index_mode: entity.get().copied(),
+            silent: entity.get().copied(),
+            tether: entity.get().copied(),
+            collider_tween: entity.get().copied(),
+            speculative_margin: entity.get().copied(),
+            avoidance: entity.get().copied(),
+            hitbox_kind: entity.get().copied(),
+            physics_group: entity.get().copied(),
+            wall_slide: entity.get().copied(),
+            spring_follower: entity.get().copied(),
+            collision_exceptions: entity.get().cloned(),
+            render_offset: entity.get().copied(),
+            no_interpolation: entity.get().copied(),
+        }
+    }
+
+    /// Returns true if the entity had none of the physics components.
+    pub fn is_empty(&self) -> bool {
+        self.position.is_none()
+            && self.movement.is_none()
+            && self.collider.is_none()
+            && self.push_priority.is_none()
+            && self.platform.is_none()
+            && self.welded_to.is_none()
+            && self.rigid_group.is_none()
+            && self.distance_joint.is_none()
+            && self.constant_force.is_none()
+            && self.seek_target.is_none()
+            && self.flee_point.is_none()
+            && self.friction.is_none()
+            && self.step_up.is_none()
+            && self.user_data.is_none()
+            && self.sensor_group.is_none()
+            && self.track_contacts.is_none()
+            && self.destructible.is_none()
+            && self.simulation_anchor.is_none()
+            && self.active_static.is_none()
+            && self.collision_layers.is_none()
+            && self.authority.is_none()
+            && self.density.is_none()
+            && self.contact_damage.is_none()
+            && self.linear_displacement.is_none()
+            && self.simplified_physics.is_none()
+            && self.sync_mode.is_none()
+            && self.z_index_mode.is_none()
+            && self.silent.is_none()
+            && self.tether.is_none()
+            && self.collider_tween.is_none()
+            && self.speculative_margin.is_none()
+            && self.avoidance.is_none()
+            && self.hitbox_kind.is_none()
+            && self.physics_group.is_none()
+            && self.wall_slide.is_none()
+            && self.spring_follower.is_none()
+            && self.collision_exceptions.is_none()
+            && self.render_offset.is_none()
+            && self.no_interpolation.is_none()
+    }
+
+    fn insert_into(self, mut entity: EntityWorldMut) {
+        // the position goes first so the collider is registered in the grid where it belongs
+        if let Some(position) = self.position {
+            entity.insert(position);
+        }
+        if let Some(movement) = self.movement {
+            entity.insert(movement);
+        }
+        if let Some(collider) = self.collider {
+            entity.insert(collider);
+        }
+        if let Some(push_priority) = self.push_priority {
+            entity.insert(push_priority);
+        }
+        if let Some(platform) = self.platform {
+            entity.insert(platform);
+        }
+        if let Some(welded_to) = self.welded_to {
+            entity.insert(welded_to);
+        }
+        if let Some(rigid_group) = self.rigid_group {
+            entity.insert(rigid_group);
+        }
+        if let Some(distance_joint) = self.distance_joint {
+            entity.insert(distance_joint);
+        }
+        if let Some(constant_force) = self.constant_force {
+            entity.insert(constant_force);
+        }
+        if let Some(seek_target) = self.seek_target {
+            entity.insert(seek_target);
+        }
+        if let Some(flee_point) = self.flee_point {
+            entity.insert(flee_point);
+        }
+        if let Some(friction) = self.friction {
+            entity.insert(friction);
+        }
+        if let Some(step_up) = self.step_up {
+            entity.insert(step_up);
+        }
+        if let Some(user_data) = self.user_data {
+            entity.insert(user_data);
+        }
+        if let Some(sensor_group) = self.sensor_group {
+            entity.insert(sensor_group);
+        }
+        if let Some(track_contacts) = self.track_contacts {
+            entity.insert(track_contacts);
+        }
+        if let Some(destructible) = self.destructible {
+            entity.insert(destructible);
+        }
+        if let Some(simulation_anchor) = self.simulation_anchor {
+            entity.insert(simulation_anchor);
+        }
+        if let Some(active_static) = self.active_static {
+            entity.insert(active_static);
+        }
+        if let Some(collision_layers) = self.collision_layers {
+            entity.insert(collision_layers);
+        }
+        if let Some(authority) = self.authority {
+            entity.insert(authority);
+        }
+        if let Some(density) = self.density {
+            entity.insert(density);
+        }
+        if let Some(contact_damage) = self.contact_damage {
+            entity.insert(contact_damage);
+        }
+        if let Some(linear_displacement) = self.linear_displacement {
+            entity.insert(linear_displacement);
+        }
+        if let Some(simplified_physics) = self.simplified_physics {
+            entity.insert(simplified_physics);
+        }
+        if let Some(sync_mode) = self.sync_mode {
+            entity.insert(sync_mode);
+        }
+        if let Some(z_index_mode) = self.z_index_mode {
+            entity.insert(z_index_mode);
+        }
+        if let Some(silent) = self.silent {
+            entity.insert(silent);
+        }
+        if let Some(tether) = self.tether {
+            entity.insert(tether);
+        }
+        if let Some(collider_tween) = self.collider_tween {
+            entity.insert(collider_tween);
+        }
+        if let Some(speculative_margin) = self.speculative_margin {
+            entity.insert(speculative_margin);
+        }
+        if let Some(avoidance) = self.avoidance {
+            entity.insert(avoidance);
+        }
+        if let Some(hitbox_kind) = self.hitbox_kind {
+            entity.insert(hitbox_kind);
+        }
+        if let Some(physics_group) = self.physics_group {
+            entity.insert(physics_group);
+        }
+        if let Some(wall_slide) = self.wall_slide {
+            entity.insert(wall_slide);
+        }
+        if let Some(spring_follower) = self.spring_follower {
+            entity.insert(spring_follower);
+        }
+        if let Some(collision_exceptions) = self.collision_exceptions {
+            entity.insert(collision_exceptions);
+        }
+        if let Some(render_offset) = self.render_offset {
+            entity.insert(render_offset);
+        }
+        if let Some(no_interpolation) = self.no_interpolation {
+            entity.insert(no_interpolation);
+        }
+    }
+}
+
+impl MapEntities for BodyState {
+    fn map_entities<E: EntityMapper>(&mut self, entity_mapper: &mut E) {
+        if let Some(platform) = &mut self.platform {
+            // riders are found again on the next tick
+            platform.riders.clear();
+        }
+        if let Some(welded_to) = &mut self.welded_to {
+            welded_to.parent = entity_mapper.get_mapped(welded_to.parent);
+        }
+        if let Some(rigid_group) = &mut self.rigid_group {
+            rigid_group.root = entity_mapper.get_mapped(rigid_group.root);
+        }
+        if let Some(distance_joint) = &mut self.distance_joint {
+            distance_joint.a = entity_mapper.get_mapped(distance_joint.a);
+            distance_joint.b = entity_mapper.get_mapped(distance_joint.b);
+        }
+        if let Some(seek_target) = &mut self.seek_target {
+            seek_target.entity = entity_mapper.get_mapped(seek_target.entity);
+        }
+        if let Some(TetherConstraint {
+            anchor: TetherAnchor::Entity(anchor),
+            ..
+        }) = &mut self.tether
+        {
+            *anchor = entity_mapper.get_mapped(*anchor);
+        }
+        if let Some(spring_follower) = &mut self.spring_follower {
+            spring_follower.target = entity_mapper.get_mapped(spring_follower.target);
+        }
+        if let Some(collision_exceptions) = &mut self.collision_exceptions {
+            collision_exceptions.ignored = std::mem::take(&mut collision_exceptions.ignored)
+                .into_iter()
+                .map(|(other, remaining)| (entity_mapper.get_mapped(other), remaining))
+                .collect();
+        }
+    }
+}
@@ -0,0 +1,31 @@
+//! Bodies in a disabled `PhysicsGroup`.
+//!
+//! Needs the `test-utils` feature: `cargo test --features test-utils --test activation`.
+
+use bevy_math::prelude::*;
+use pvw_rrect_physics::{prelude::*, test_utils::*};
+
+#[test]
+fn paused_group_stays_put_under_a_tether() {
+    let mut app = physics_test_app();
+    app.world_mut().resource_mut::<PhysicsGroups>().disable(1);
+
+    // far outside the tether, which pulls it in as soon as it is simulated
+    let start = vec2(10.0, 0.0);
+    let body = app
+        .world_mut()
+        .spawn((
+            PhysicsGroup(1),
+            Position(start),
+            Collider::rect(Vec2::ONE, ColliderType::Dynamic(1.0)),
+            TetherConstraint::new(TetherAnchor::Point(Vec2::ZERO), 2.0),
+        ))
+        .id();
+
+    advance_ticks(&mut app, 16);
+    assert_position_near(&app, body, start, 1e-6);
+
+    app.world_mut().resource_mut::<PhysicsGroups>().enable(1);
+    advance_ticks(&mut app, 1);
+    assert_position_near(&app, body, vec2(2.0, 0.0), 1e-3);
+}
@@ -0,0 +1,38 @@
+//! Bodies simulated by another peer, seen from the server.
+//!
+//! Needs the `test-utils` feature: `cargo test --features test-utils --test authority`.
+
+use bevy_math::prelude::*;
+use pvw_rrect_physics::{prelude::*, test_utils::*};
+
+#[test]
+fn client_body_pushes_server_npc() {
+    let mut app = physics_test_app();
+    let world = app.world_mut();
+    let player = world
+        .spawn((
+            Authority::Client(1),
+            Position(vec2(-2.0, 0.0)),
+            Collider::rect(Vec2::ONE, ColliderType::Dynamic(1.0)),
+        ))
+        .id();
+    let npc = world
+        .spawn((
+            Position(Vec2::ZERO),
+            Collider::rect(Vec2::ONE, ColliderType::Dynamic(1.0)),
+        ))
+        .id();
+    advance_ticks(&mut app, 1);
+
+    // the client walks right, the server only receives its positions
+    for tick in 1..=40 {
+        let replicated = vec2(-2.0 + tick as f32 * 0.1, 0.0);
+        app.world_mut().get_mut::<Position>(player).unwrap().0 = replicated;
+        advance_ticks(&mut app, 1);
+        assert_position_near(&app, player, replicated, 1e-6);
+    }
+
+    // the npc is kept just in front of the player instead of blocking it
+    assert_position_near(&app, npc, vec2(3.0, 0.0), 0.05);
+    assert_no_overlaps(&mut app);
+}
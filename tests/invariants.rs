@@ -0,0 +1,125 @@
+//! Randomized headless scenes that check invariants every solver change has to keep.
+//!
+//! Needs the `test-utils` feature: `cargo test --features test-utils --test invariants`.
+
+use bevy_math::prelude::*;
+use pvw_rrect_physics::{prelude::*, test_utils::*};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+const SEEDS: u64 = 8;
+const BODIES: usize = 48;
+const PILLARS: usize = 4;
+/// Half the side of the walled arena, in tiles
+const HALF_EXTENT: f32 = 12.0;
+
+fn spawn_scene(app: &mut bevy_app::App, rng: &mut StdRng) {
+    let world = app.world_mut();
+
+    let wall = HALF_EXTENT + 0.5;
+    for (pos, size) in [
+        (vec2(0.0, wall), vec2(wall * 2.0 + 1.0, 1.0)),
+        (vec2(0.0, -wall), vec2(wall * 2.0 + 1.0, 1.0)),
+        (vec2(wall, 0.0), vec2(1.0, wall * 2.0 + 1.0)),
+        (vec2(-wall, 0.0), vec2(1.0, wall * 2.0 + 1.0)),
+    ] {
+        world.spawn((Position(pos), Collider::rect(size, ColliderType::Static)));
+    }
+
+    for _ in 0..PILLARS {
+        let limit = HALF_EXTENT - 2.0;
+        let pos = vec2(
+            rng.random_range(-limit..limit),
+            rng.random_range(-limit..limit),
+        );
+        world.spawn((
+            Position(pos),
+            Collider::rect(Vec2::ONE, ColliderType::Static),
+        ));
+    }
+
+    for _ in 0..BODIES {
+        let size = vec2(rng.random_range(0.3..1.5), rng.random_range(0.3..1.5));
+        let radius = size.min_element() * 0.5 * rng.random_range(0.0..1.0);
+        let ctype = ColliderType::Dynamic(rng.random_range(0.5..4.0));
+        let limit = HALF_EXTENT - 1.0;
+        let pos = vec2(
+            rng.random_range(-limit..limit),
+            rng.random_range(-limit..limit),
+        );
+
+        // damped, so the scene comes to rest once the forces stop
+        let mut movement = Movement::damped(Vec2::splat(8.0));
+        movement.apply_force(PartialForce {
+            id: "wander".to_owned(),
+            force: Some(vec2(
+                rng.random_range(-8.0..8.0),
+                rng.random_range(-8.0..8.0),
+            )),
+            active: Some(true),
+            ..Default::default()
+        });
+
+        world.spawn((
+            Position(pos),
+            Collider::new(size, radius, ctype),
+            movement,
+            LinearDisplacement::default(),
+        ));
+    }
+}
+
+fn stop_all(app: &mut bevy_app::App) {
+    let world = app.world_mut();
+    for mut movement in world.query::<&mut Movement>().iter_mut(world) {
+        movement.apply_force(PartialForce {
+            id: "wander".to_owned(),
+            active: Some(false),
+            ..Default::default()
+        });
+    }
+}
+
+#[test]
+fn random_scenes_keep_invariants() {
+    for seed in 0..SEEDS {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut app = physics_test_app();
+        spawn_scene(&mut app, &mut rng);
+
+        // bodies push each other around while their forces are active
+        for _ in 0..120 {
+            advance_ticks(&mut app, 1);
+            assert_finite_positions(&mut app);
+            assert_speeds_bounded(&mut app);
+            assert_grid_consistent(&app);
+        }
+
+        stop_all(&mut app);
+        advance_ticks(&mut app, 240);
+
+        assert_finite_positions(&mut app);
+        assert_grid_consistent(&app);
+        assert_no_overlaps(&mut app);
+    }
+}
+
+#[test]
+fn despawned_bodies_leave_the_grid() {
+    let mut rng = StdRng::seed_from_u64(SEEDS);
+    let mut app = physics_test_app();
+    spawn_scene(&mut app, &mut rng);
+    advance_ticks(&mut app, 10);
+
+    let world = app.world_mut();
+    let bodies: Vec<_> = world
+        .query_filtered::<bevy_ecs::entity::Entity, bevy_ecs::query::With<Movement>>()
+        .iter(world)
+        .step_by(2)
+        .collect();
+    for entity in bodies {
+        world.despawn(entity);
+    }
+
+    advance_ticks(&mut app, 10);
+    assert_grid_consistent(&app);
+}
@@ -0,0 +1,76 @@
+//! Clusters of bodies far apart from each other are solved as separate islands.
+//!
+//! Needs the `test-utils` feature: `cargo test --features test-utils --test islands`.
+
+use bevy_ecs::prelude::*;
+use bevy_math::prelude::*;
+use pvw_rrect_physics::{prelude::*, test_utils::*};
+
+const TICKS: u32 = 30;
+
+/// Spawns a row of overlapping boxes pushed into a corner, returning the boxes.
+///
+/// Without `solid` only the entities are spawned, so the other cluster keeps the same entities.
+fn spawn_cluster(world: &mut World, offset: Vec2, push: Vec2, solid: bool) -> Vec<Entity> {
+    let walls = [
+        (vec2(4.0, 0.0), vec2(1.0, 9.0)),
+        (vec2(0.0, -4.0), vec2(9.0, 1.0)),
+    ];
+    for (pos, size) in walls {
+        let mut wall = world.spawn(Position(offset + pos));
+        if solid {
+            wall.insert(Collider::rect(size, ColliderType::Static));
+        }
+    }
+
+    (0..6)
+        .map(|i| {
+            let i = i as f32;
+            let mut movement = Movement::default();
+            movement.apply_force(PartialForce {
+                id: "push".to_owned(),
+                force: Some(push),
+                active: Some(true),
+                ..Default::default()
+            });
+
+            let mut body = world.spawn(Position(offset + vec2(i * 0.8 - 1.0, i * 0.3 - 2.5)));
+            if solid {
+                body.insert((
+                    Collider::rect(Vec2::ONE, ColliderType::Dynamic(1.0 + i)),
+                    movement,
+                ));
+            }
+            body.id()
+        })
+        .collect()
+}
+
+/// Runs the two clusters, each only if enabled, and returns where their boxes ended up.
+fn run(first: bool, second: bool) -> (Vec<Vec2>, Vec<Vec2>) {
+    let mut app = physics_test_app();
+    let world = app.world_mut();
+    let a = spawn_cluster(world, Vec2::ZERO, vec2(20.0, -10.0), first);
+    let b = spawn_cluster(world, vec2(100.0, 40.0), vec2(14.0, -18.0), second);
+
+    advance_ticks(&mut app, TICKS);
+    assert_no_overlaps(&mut app);
+
+    let positions = |bodies: Vec<Entity>| {
+        bodies
+            .into_iter()
+            .map(|body| app.world().get::<Position>(body).unwrap().0)
+            .collect::<Vec<_>>()
+    };
+    (positions(a), positions(b))
+}
+
+#[test]
+fn far_clusters_resolve_as_if_alone() {
+    let (together_a, together_b) = run(true, true);
+    let (alone_a, _) = run(true, false);
+    let (_, alone_b) = run(false, true);
+
+    assert_eq!(together_a, alone_a);
+    assert_eq!(together_b, alone_b);
+}
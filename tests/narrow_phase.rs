@@ -0,0 +1,64 @@
+//! Property tests for `Collider::penetration`, the MTV every collision response is based on.
+
+use bevy_math::prelude::*;
+use proptest::prelude::*;
+use pvw_rrect_physics::{Collider, ColliderType};
+
+/// Small enough that the float error of a translation stays below it.
+const EPSILON: f32 = 1e-4;
+
+fn collider() -> impl Strategy<Value = Collider> {
+    (0.01f32..8.0, 0.01f32..8.0, 0.0f32..1.0).prop_map(|(w, h, roundness)| {
+        let radius = w.min(h) * 0.5 * roundness;
+        Collider::new(vec2(w, h), radius, ColliderType::Dynamic(1.0))
+    })
+}
+
+fn offset() -> impl Strategy<Value = Vec2> {
+    (-10.0f32..10.0, -10.0f32..10.0).prop_map(|(x, y)| vec2(x, y))
+}
+
+proptest! {
+    #[test]
+    fn mtv_is_finite(a in collider(), b in collider(), offset in offset()) {
+        if let Some(mtv) = a.penetration(Vec2::ZERO, &b, offset) {
+            prop_assert!(mtv.is_finite(), "{mtv}");
+        }
+    }
+
+    #[test]
+    fn concentric_mtv_is_finite(a in collider(), b in collider()) {
+        let mtv = a.penetration(Vec2::ZERO, &b, Vec2::ZERO);
+        prop_assert!(mtv.is_some_and(|mtv| mtv.is_finite() && mtv != Vec2::ZERO), "{mtv:?}");
+    }
+
+    #[test]
+    fn resolution_separates(a in collider(), b in collider(), offset in offset()) {
+        if let Some(mtv) = a.penetration(Vec2::ZERO, &b, offset) {
+            let left = a.penetration(-mtv, &b, offset);
+            prop_assert!(left.is_none_or(|left| left.length() < EPSILON), "{mtv} left {left:?}");
+        }
+    }
+
+    #[test]
+    fn mtv_is_no_longer_than_minimal_axis(a in collider(), b in collider(), offset in offset()) {
+        if let Some(mtv) = a.penetration(Vec2::ZERO, &b, offset) {
+            // moving apart along either axis by the bounding box overlap always separates them
+            let aabb_overlap = (a.size + b.size) * 0.5 - offset.abs();
+            prop_assert!(mtv.length() <= aabb_overlap.min_element() + EPSILON, "{mtv} {aabb_overlap}");
+        }
+    }
+
+    #[test]
+    fn mtv_is_antisymmetric(a in collider(), b in collider(), offset in offset()) {
+        // the direction is arbitrary for centers aligned on an axis
+        prop_assume!(offset.x != 0.0 && offset.y != 0.0);
+
+        let ab = a.penetration(Vec2::ZERO, &b, offset);
+        let ba = b.penetration(offset, &a, Vec2::ZERO);
+        match (ab, ba) {
+            (Some(ab), Some(ba)) => prop_assert!((ab + ba).length() < EPSILON, "{ab} {ba}"),
+            (ab, ba) => prop_assert!(ab.is_none() && ba.is_none(), "{ab:?} {ba:?}"),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+//! How `Transform` follows `Position` in the singleplayer plugin.
+//!
+//! Needs the `singleplayer` feature, which is enabled by default.
+
+use bevy_app::prelude::*;
+use bevy_math::prelude::*;
+use bevy_time::{TimePlugin, TimeUpdateStrategy, prelude::*};
+use bevy_transform::components::Transform;
+use pvw_rrect_physics::prelude::*;
+
+const WORLD_SIZE: Vec2 = vec2(10.0, 10.0);
+
+fn torus_app(pixel_snap: bool) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        TimePlugin,
+        PhysicsPluginBuilder::new()
+            .config(PhysicsConfig {
+                topology: WorldTopology::Torus { size: WORLD_SIZE },
+                ..Default::default()
+            })
+            .interpolation(Interpolation::Lerp(0.2))
+            .pixel_snap(pixel_snap)
+            .build(),
+    ));
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(
+        Time::<Fixed>::default().timestep(),
+    ));
+    app
+}
+
+#[test]
+fn wrapping_body_is_drawn_at_the_other_edge() {
+    for pixel_snap in [false, true] {
+        let mut app = torus_app(pixel_snap);
+        let tile_size = *app.world().resource::<TileSize>();
+
+        let mut movement = Movement::default();
+        movement.apply_force(PartialForce {
+            id: "walk".to_owned(),
+            force: Some(vec2(8.0, 0.0)),
+            active: Some(true),
+            ..Default::default()
+        });
+        let body = app
+            .world_mut()
+            .spawn((
+                Position(vec2(8.0, 5.0)),
+                movement,
+                Collider::rect(Vec2::ONE, ColliderType::Dynamic(1.0)),
+                Transform::default(),
+            ))
+            .id();
+
+        let mut wrapped = false;
+        for _ in 0..64 {
+            app.update();
+
+            let pos = app.world().get::<Position>(body).unwrap().0;
+            let translation = app.world().get::<Transform>(body).unwrap().translation;
+            let drawn = translation.truncate() / tile_size.size();
+            wrapped |= pos.x < 5.0;
+
+            // lagging behind is fine, drawing the body in the middle of the world is not
+            let lag = (pos - drawn).abs();
+            assert!(
+                lag.x < 2.0 && lag.y < 2.0,
+                "pixel_snap {pixel_snap}: drawn at {drawn} for {pos}"
+            );
+        }
+
+        assert!(wrapped, "the body never crossed the edge");
+    }
+}
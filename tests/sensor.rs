@@ -0,0 +1,74 @@
+//! Overlap messages of sensors without a `SensorFilter`.
+//!
+//! Needs the `test-utils` feature: `cargo test --features test-utils --test sensor`.
+
+use bevy_ecs::prelude::*;
+use bevy_math::prelude::*;
+use pvw_rrect_physics::{prelude::*, test_utils::*};
+
+#[derive(Resource, Default)]
+struct Overlaps {
+    started: usize,
+    ended: usize,
+}
+
+fn count_overlaps(
+    mut started: MessageReader<SensorOverlapStarted>,
+    mut ended: MessageReader<SensorOverlapEnded>,
+    mut overlaps: ResMut<Overlaps>,
+) {
+    overlaps.started += started.read().count();
+    overlaps.ended += ended.read().count();
+}
+
+#[test]
+fn layer_flip_reports_one_overlap_change() {
+    let mut app = physics_test_app();
+    app.init_resource::<Overlaps>();
+    app.add_systems(bevy_app::FixedPostUpdate, count_overlaps);
+    // a budget of one pair defers the sensor pair every other tick
+    app.insert_resource(PhysicsConfig {
+        max_pairs: Some(1),
+        ..Default::default()
+    });
+
+    let world = app.world_mut();
+    world.spawn((
+        Position(Vec2::ZERO),
+        Collider::rect(vec2(4.0, 4.0), ColliderType::Sensor),
+    ));
+    // another pair far away for the budget
+    world.spawn((
+        Position(vec2(50.0, 0.0)),
+        Collider::rect(Vec2::ONE, ColliderType::Static),
+        ActiveStatic,
+    ));
+    world.spawn((
+        Position(vec2(50.5, 0.0)),
+        Collider::rect(Vec2::ONE, ColliderType::Static),
+    ));
+    let player = world
+        .spawn((
+            Position(vec2(-0.5, 0.0)),
+            Collider::rect(Vec2::ONE, ColliderType::Dynamic(1.0)),
+        ))
+        .id();
+
+    advance_ticks(&mut app, 8);
+    let overlaps = app.world().resource::<Overlaps>();
+    assert_eq!((overlaps.started, overlaps.ended), (1, 0));
+
+    app.world_mut()
+        .entity_mut(player)
+        .insert(CollisionLayers::new(LayerMask::layer(1), LayerMask::NONE));
+    advance_ticks(&mut app, 8);
+    let overlaps = app.world().resource::<Overlaps>();
+    assert_eq!((overlaps.started, overlaps.ended), (1, 1));
+
+    app.world_mut()
+        .entity_mut(player)
+        .remove::<CollisionLayers>();
+    advance_ticks(&mut app, 8);
+    let overlaps = app.world().resource::<Overlaps>();
+    assert_eq!((overlaps.started, overlaps.ended), (2, 1));
+}